@@ -0,0 +1,179 @@
+//! Rendering of lint findings for continuous-integration systems, which
+//! annotate the offending line of a file.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use crate::io::normalize_path;
+
+/// A lint finding located in a file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Annotation {
+    /// Path to the file, relative to the monorepo root.
+    pub path: PathBuf,
+    /// 1-based line number, when known.
+    pub line: Option<usize>,
+    /// Identifier of the violated rule, such as `unpinned-dependency`.
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl Annotation {
+    pub fn new<P>(path: P, line: Option<usize>, rule: &'static str, message: String) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            line,
+            rule,
+            message,
+        }
+    }
+}
+
+/// Path of an annotated file relative to the current directory, which is
+/// the repository root in CI.
+fn display_path(root: &Path, path: &Path) -> String {
+    normalize_path(&root.join(path))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Escape data of a GitHub Actions workflow command.
+fn escape_github_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a GitHub Actions workflow command.
+fn escape_github_property(property: &str) -> String {
+    escape_github_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// Render annotations as GitHub Actions `::error` workflow commands, one per
+/// line.
+pub fn github(root: &Path, annotations: &[Annotation]) -> String {
+    annotations
+        .iter()
+        .map(|annotation| {
+            let mut properties = format!(
+                "file={}",
+                escape_github_property(&display_path(root, &annotation.path))
+            );
+            if let Some(line) = annotation.line {
+                properties.push_str(&format!(",line={}", line));
+            }
+            format!(
+                "::error {},title={}::{}\n",
+                properties,
+                escape_github_property(annotation.rule),
+                escape_github_data(&annotation.message)
+            )
+        })
+        .collect()
+}
+
+/// Render annotations as a SARIF 2.1.0 log. `rules` pairs each rule
+/// identifier with a short description.
+pub fn sarif(root: &Path, annotations: &[Annotation], rules: &[(&str, &str)]) -> serde_json::Value {
+    let rules: Vec<_> = rules
+        .iter()
+        .map(|(id, description)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": description },
+            })
+        })
+        .collect();
+    let results: Vec<_> = annotations
+        .iter()
+        .map(|annotation| {
+            let mut physical_location = json!({
+                "artifactLocation": { "uri": display_path(root, &annotation.path) },
+            });
+            if let Some(line) = annotation.line {
+                physical_location["region"] = json!({ "startLine": line });
+            }
+            json!({
+                "ruleId": annotation.rule,
+                "level": "error",
+                "message": { "text": annotation.message },
+                "locations": [{ "physicalLocation": physical_location }],
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "monorepo",
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// Find the 1-based line of a nested property in JSON text, such as
+/// `["dependencies", "react"]`. Only keys are matched, so the lookup is
+/// approximate for documents repeating a key at several depths.
+pub(crate) fn json_property_line(contents: &str, property_path: &[&str]) -> Option<usize> {
+    let mut offset = 0;
+    for key in property_path {
+        let quoted_key = serde_json::to_string(key).ok()?;
+        offset += contents[offset..]
+            .match_indices(&quoted_key)
+            .map(|(index, _)| index + quoted_key.len())
+            .find(|end| contents[offset + end..].trim_start().starts_with(':'))?;
+    }
+    Some(contents[..offset].matches('\n').count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{github, json_property_line, Annotation};
+
+    #[test]
+    fn should_find_nested_property_line() {
+        let contents = r#"{
+  "name": "dependencies",
+  "dependencies": {
+    "a": "1.0.0",
+    "b": "1.0.0"
+  }
+}"#;
+        assert_eq!(
+            Some(5),
+            json_property_line(contents, &["dependencies", "b"])
+        );
+        assert_eq!(None, json_property_line(contents, &["devDependencies"]));
+    }
+
+    #[test]
+    fn should_render_github_workflow_commands() {
+        let annotations = [Annotation::new(
+            "packages/a/package.json",
+            Some(4),
+            "unpinned-dependency",
+            String::from("expected 1.0.0\nfound 0.0.0"),
+        )];
+        assert_eq!(
+            "::error file=packages/a/package.json,line=4,title=unpinned-dependency::expected 1.0.0%0Afound 0.0.0\n",
+            github(Path::new("."), &annotations)
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use askama::Template;
+
+use crate::configuration_file::{write_all_text, ConfigurationFile, WriteError};
+use crate::conventional_commit::ConventionalCommit;
+use crate::git::{self, Commit, GitError};
+use crate::io::{read_to_string, FromFileError};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::{DependencyGroup, PackageManifest};
+use crate::release::{package_commits, ReleaseError};
+
+#[derive(Template)]
+#[template(path = "changelog.md")]
+struct ChangelogTemplate<'a> {
+    version: &'a str,
+    groups: &'a [ChangelogGroup],
+    updated_dependencies: &'a [String],
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ChangelogError {
+    pub kind: ChangelogErrorKind,
+}
+
+impl Display for ChangelogError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "error generating changelogs")
+    }
+}
+
+impl std::error::Error for ChangelogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ChangelogErrorKind::FromFile(err) => Some(err),
+            ChangelogErrorKind::EnumeratePackageManifests(err) => Some(err),
+            ChangelogErrorKind::Release(err) => Some(err),
+            ChangelogErrorKind::Git(err) => Some(err),
+            ChangelogErrorKind::Write(err) => Some(err),
+        }
+    }
+}
+
+impl From<FromFileError> for ChangelogError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ChangelogErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for ChangelogError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: ChangelogErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<ReleaseError> for ChangelogError {
+    fn from(err: ReleaseError) -> Self {
+        Self {
+            kind: ChangelogErrorKind::Release(err),
+        }
+    }
+}
+
+impl From<GitError> for ChangelogError {
+    fn from(err: GitError) -> Self {
+        Self {
+            kind: ChangelogErrorKind::Git(err),
+        }
+    }
+}
+
+impl From<WriteError> for ChangelogError {
+    fn from(err: WriteError) -> Self {
+        Self {
+            kind: ChangelogErrorKind::Write(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ChangelogErrorKind {
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    Release(ReleaseError),
+    #[non_exhaustive]
+    Git(GitError),
+    #[non_exhaustive]
+    Write(WriteError),
+}
+
+/// Entries under a heading of a changelog section, such as "Features".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct ChangelogGroup {
+    pub title: &'static str,
+    pub entries: Vec<String>,
+}
+
+/// A new section of a package's CHANGELOG.md.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct PackageChangelog {
+    pub name: String,
+    /// Path to CHANGELOG.md, relative to the monorepo root.
+    pub path: PathBuf,
+    pub section: String,
+}
+
+impl Display for PackageChangelog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}:", self.path.display())?;
+        writeln!(f)?;
+        write!(f, "{}", self.section)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Changelogs(Vec<PackageChangelog>);
+
+impl Changelogs {
+    pub(crate) fn new(mut changelogs: Vec<PackageChangelog>) -> Self {
+        // Sort by path for deterministic output
+        changelogs.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        Self(changelogs)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageChangelog> {
+        self.0.iter()
+    }
+}
+
+impl Display for Changelogs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, changelog) in self.0.iter().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", changelog)?;
+        }
+        Ok(())
+    }
+}
+
+const CHANGELOG_FILENAME: &str = "CHANGELOG.md";
+
+/// Group the conventional commits which call for a release, newest first.
+fn commit_groups(commits: &[Commit]) -> Vec<ChangelogGroup> {
+    let mut groups = [
+        ("Breaking Changes", Vec::new()),
+        ("Features", Vec::new()),
+        ("Bug Fixes", Vec::new()),
+        ("Performance Improvements", Vec::new()),
+    ];
+    for commit in commits {
+        let conventional_commit = match ConventionalCommit::parse(&commit.subject, &commit.body) {
+            Some(conventional_commit) => conventional_commit,
+            None => continue,
+        };
+        let index = match conventional_commit.kind.as_str() {
+            _ if conventional_commit.breaking => 0,
+            "feat" => 1,
+            "fix" => 2,
+            "perf" => 3,
+            _ => continue,
+        };
+        let entry = match &conventional_commit.scope {
+            Some(scope) => format!(
+                "**{}:** {} ({})",
+                scope,
+                conventional_commit.description,
+                commit.short_hash()
+            ),
+            None => format!(
+                "{} ({})",
+                conventional_commit.description,
+                commit.short_hash()
+            ),
+        };
+        groups[index].1.push(entry);
+    }
+    groups
+        .into_iter()
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(title, entries)| ChangelogGroup { title, entries })
+        .collect()
+}
+
+/// Internal dependencies whose specifiers changed since `revision`, such as
+/// by `monorepo pin` after a dependency was bumped, as `<name>@<version>`.
+fn updated_dependencies(
+    root: &Path,
+    revision: &str,
+    package_manifest: &PackageManifest,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Result<BTreeSet<String>, ChangelogError> {
+    let previous: serde_json::Value = match git::show(root, revision, &package_manifest.path())? {
+        Some(contents) => serde_json::from_str(&contents).unwrap_or_default(),
+        None => return Ok(BTreeSet::new()),
+    };
+
+    let mut updated = BTreeSet::new();
+    for dependency_group in DependencyGroup::VALUES {
+        let dependencies = package_manifest
+            .contents
+            .extra_fields
+            .get(dependency_group)
+            .and_then(serde_json::Value::as_object);
+        for (dependency, specifier) in dependencies.into_iter().flatten() {
+            let dependency_manifest = match package_manifests_by_package_name.get(dependency) {
+                Some(dependency_manifest) => dependency_manifest,
+                None => continue,
+            };
+            if previous[dependency_group][dependency] != *specifier {
+                updated.insert(format!(
+                    "{}@{}",
+                    dependency, dependency_manifest.contents.version
+                ));
+            }
+        }
+    }
+    Ok(updated)
+}
+
+/// Render a new changelog section for a package, or `None` when the
+/// package's CHANGELOG.md already contains a section for `version`.
+pub(crate) fn package_changelog(
+    root: &Path,
+    package_manifest: &PackageManifest,
+    version: &str,
+    groups: &[ChangelogGroup],
+    updated_dependencies: &[String],
+) -> Result<Option<PackageChangelog>, ChangelogError> {
+    let path = package_manifest.directory().join(CHANGELOG_FILENAME);
+    let filename = root.join(&path);
+    if filename.exists() {
+        let heading = format!("## {}", version);
+        let existing = read_to_string(&filename)?;
+        if existing.lines().any(|line| line.trim_end() == heading) {
+            return Ok(None);
+        }
+    }
+
+    let section = ChangelogTemplate {
+        version,
+        groups,
+        updated_dependencies,
+    }
+    .render()
+    .expect("Unable to render changelog template");
+    Ok(Some(PackageChangelog {
+        name: package_manifest.contents.name.to_owned(),
+        path,
+        section,
+    }))
+}
+
+/// Insert a section after the title of a changelog, if it has one.
+fn prepend_section(existing: &str, section: &str) -> String {
+    let (title, rest) = match existing.strip_prefix("# ") {
+        Some(_) => existing.split_once('\n').unwrap_or((existing, "")),
+        None => ("# Changelog", existing),
+    };
+    let rest = rest.trim_start_matches('\n');
+    match rest.is_empty() {
+        true => format!("{}\n\n{}", title, section),
+        false => format!("{}\n\n{}\n{}", title, section, rest),
+    }
+}
+
+/// The contents of each CHANGELOG.md with its section prepended, keyed by
+/// path including `root`. Missing files start out empty.
+pub(crate) fn changelog_contents(
+    root: &Path,
+    changelogs: &Changelogs,
+) -> Result<Vec<(PathBuf, String)>, ChangelogError> {
+    changelogs
+        .iter()
+        .map(|changelog| {
+            let filename = root.join(&changelog.path);
+            let existing = match filename.exists() {
+                true => read_to_string(&filename)?,
+                false => String::new(),
+            };
+            Ok((filename, prepend_section(&existing, &changelog.section)))
+        })
+        .collect()
+}
+
+/// Prepend each section to its CHANGELOG.md, creating files as necessary.
+/// Every file is read before any is written, and none is replaced unless
+/// every one of them can be written.
+pub(crate) fn write_changelogs(root: &Path, changelogs: &Changelogs) -> Result<(), ChangelogError> {
+    Ok(write_all_text(changelog_contents(root, changelogs)?)?)
+}
+
+/// Render a changelog section for each internal package with conventional
+/// commits calling for a release since its last `<name>@<version>` tag, or
+/// with internal dependencies re-pinned since that tag. Sections are titled
+/// with the version in each package.json, so run this after bumping
+/// versions.
+pub fn plan<P>(root: P) -> Result<Changelogs, ChangelogError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<Changelogs, ChangelogError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        let package_commits = package_commits(root, &package_manifests_by_package_name)?;
+
+        let mut changelogs = Vec::new();
+        for (package_name, package_commits) in package_commits {
+            let package_manifest = &package_manifests_by_package_name[&package_name];
+            let groups = commit_groups(&package_commits.commits);
+            let updated_dependencies: Vec<String> = match &package_commits.last_tag {
+                Some(last_tag) => updated_dependencies(
+                    root,
+                    last_tag,
+                    package_manifest,
+                    &package_manifests_by_package_name,
+                )?
+                .into_iter()
+                .collect(),
+                None => Vec::new(),
+            };
+            if groups.is_empty() && updated_dependencies.is_empty() {
+                continue;
+            }
+            if let Some(changelog) = package_changelog(
+                root,
+                package_manifest,
+                &package_manifest.contents.version,
+                &groups,
+                &updated_dependencies,
+            )? {
+                changelogs.push(changelog);
+            }
+        }
+        Ok(Changelogs::new(changelogs))
+    }
+    inner(root.as_ref())
+}
+
+/// Prepend the sections from `plan` to each package's CHANGELOG.md.
+pub fn modify<P>(root: P) -> Result<Changelogs, ChangelogError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<Changelogs, ChangelogError> {
+        let changelogs = plan(root)?;
+        write_changelogs(root, &changelogs)?;
+        Ok(changelogs)
+    }
+    inner(root.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prepend_section;
+
+    #[test]
+    fn should_prepend_section_after_title() {
+        let section = "## 1.1.0\n\n### Features\n\n- add option (abc1234)\n";
+        assert_eq!(
+            "# Changelog\n\n## 1.1.0\n\n### Features\n\n- add option (abc1234)\n",
+            prepend_section("", section)
+        );
+        assert_eq!(
+            "# @scope/a\n\n## 1.1.0\n\n### Features\n\n- add option (abc1234)\n\n## 1.0.0\n",
+            prepend_section("# @scope/a\n\n## 1.0.0\n", section)
+        );
+        assert_eq!(
+            "# Changelog\n\n## 1.1.0\n\n### Features\n\n- add option (abc1234)\n\n## 1.0.0\n",
+            prepend_section("## 1.0.0\n", section)
+        );
+    }
+}
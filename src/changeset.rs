@@ -0,0 +1,595 @@
+//! Intent files recording which packages to release, in the format of the
+//! [changesets] tool:
+//!
+//! ```markdown
+//! ---
+//! "@scope/a": minor
+//! "@scope/b": patch
+//! ---
+//!
+//! Add an option to the parser
+//! ```
+//!
+//! [changesets]: https://github.com/changesets/changesets
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::changelog::{
+    changelog_contents, package_changelog, ChangelogError, ChangelogGroup, Changelogs,
+};
+use crate::configuration_file::{write_all_with_text, ConfigurationFile, WriteError};
+use crate::io::{read_to_string, FromFileError};
+use crate::monorepo_configuration::MonorepoConfiguration;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+use crate::semver::{ReleaseType, SemverParseError, Version};
+use crate::version::{
+    apply_versions, cascade_releases, internal_dependents, release_precedence, VersionChange,
+    VersionError, VersionPlan,
+};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ChangesetError {
+    pub kind: ChangesetErrorKind,
+}
+
+impl Display for ChangesetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ChangesetErrorKind::UnknownPackage(package_name) => {
+                write!(f, "no internal package named {}", package_name)
+            }
+            ChangesetErrorKind::InvalidChangeset { path, reason } => {
+                write!(f, "invalid changeset {:?}: {}", path, reason)
+            }
+            ChangesetErrorKind::InvalidVersion(package_name, _) => {
+                write!(f, "package {} has an invalid version", package_name)
+            }
+            ChangesetErrorKind::Io { path, .. } => {
+                write!(f, "unable to access {:?}", path)
+            }
+            ChangesetErrorKind::UnsupportedRelease(release) => write!(
+                f,
+                "changesets cannot record a {} release, use major, minor or patch",
+                release
+            ),
+            _ => write!(f, "error applying changesets"),
+        }
+    }
+}
+
+impl std::error::Error for ChangesetError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ChangesetErrorKind::FromFile(err) => Some(err),
+            ChangesetErrorKind::EnumeratePackageManifests(err) => Some(err),
+            ChangesetErrorKind::Version(err) => Some(err),
+            ChangesetErrorKind::Changelog(err) => Some(err),
+            ChangesetErrorKind::Write(err) => Some(err),
+            ChangesetErrorKind::Io { err, .. } => Some(err),
+            ChangesetErrorKind::InvalidVersion(_, err) => Some(err),
+            ChangesetErrorKind::UnknownPackage(_) => None,
+            ChangesetErrorKind::InvalidChangeset { .. } => None,
+            ChangesetErrorKind::UnsupportedRelease(_) => None,
+        }
+    }
+}
+
+impl From<FromFileError> for ChangesetError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ChangesetErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for ChangesetError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: ChangesetErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<VersionError> for ChangesetError {
+    fn from(err: VersionError) -> Self {
+        Self {
+            kind: ChangesetErrorKind::Version(err),
+        }
+    }
+}
+
+impl From<ChangelogError> for ChangesetError {
+    fn from(err: ChangelogError) -> Self {
+        Self {
+            kind: ChangesetErrorKind::Changelog(err),
+        }
+    }
+}
+
+impl From<WriteError> for ChangesetError {
+    fn from(err: WriteError) -> Self {
+        Self {
+            kind: ChangesetErrorKind::Write(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ChangesetErrorKind {
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    Version(VersionError),
+    #[non_exhaustive]
+    Changelog(ChangelogError),
+    #[non_exhaustive]
+    Write(WriteError),
+    #[non_exhaustive]
+    Io { path: PathBuf, err: io::Error },
+    #[non_exhaustive]
+    UnknownPackage(String),
+    #[non_exhaustive]
+    InvalidChangeset { path: PathBuf, reason: String },
+    #[non_exhaustive]
+    InvalidVersion(String, SemverParseError),
+    /// A release type changesets cannot record, such as a prerelease.
+    #[non_exhaustive]
+    UnsupportedRelease(ReleaseType),
+}
+
+/// Directory containing changesets, relative to the monorepo root.
+pub const CHANGESET_DIRECTORY: &str = ".changeset";
+
+/// An intent to release packages, with a summary for their changelogs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Changeset {
+    /// Path to the changeset, relative to the monorepo root.
+    pub path: PathBuf,
+    pub releases: BTreeMap<String, ReleaseType>,
+    pub summary: String,
+}
+
+impl Changeset {
+    fn parse(path: PathBuf, contents: &str) -> Result<Self, ChangesetError> {
+        let invalid = |reason: &str| ChangesetError {
+            kind: ChangesetErrorKind::InvalidChangeset {
+                path: path.to_owned(),
+                reason: reason.to_owned(),
+            },
+        };
+        let contents = contents
+            .trim_start()
+            .strip_prefix("---")
+            .ok_or_else(|| invalid("missing front matter"))?;
+        let (front_matter, summary) = contents
+            .split_once("\n---")
+            .ok_or_else(|| invalid("unterminated front matter"))?;
+
+        let mut releases = BTreeMap::new();
+        for line in front_matter.lines().map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            let (package_name, release) = line
+                .rsplit_once(':')
+                .ok_or_else(|| invalid("expected `<package>: <release type>`"))?;
+            let package_name = package_name.trim().trim_matches(|c| c == '"' || c == '\'');
+            let release = match release.trim() {
+                "major" => ReleaseType::Major,
+                "minor" => ReleaseType::Minor,
+                "patch" => ReleaseType::Patch,
+                _ => return Err(invalid("release type must be major, minor or patch")),
+            };
+            releases.insert(package_name.to_owned(), release);
+        }
+
+        Ok(Self {
+            path,
+            releases,
+            summary: summary.trim().to_owned(),
+        })
+    }
+}
+
+impl Display for Changeset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "---")?;
+        for (package_name, release) in self.releases.iter() {
+            writeln!(f, "\"{}\": {}", package_name, release)?;
+        }
+        writeln!(f, "---")?;
+        writeln!(f)?;
+        writeln!(f, "{}", self.summary)
+    }
+}
+
+/// Derive a changeset filename from the first words of its summary.
+fn changeset_filename(directory: &Path, summary: &str) -> PathBuf {
+    let slug = summary
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .take(6)
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    let slug = match slug.is_empty() {
+        true => "changeset".to_owned(),
+        false => slug,
+    };
+    let mut filename = directory.join(format!("{}.md", slug));
+    let mut suffix = 2;
+    while filename.exists() {
+        filename = directory.join(format!("{}-{}.md", slug, suffix));
+        suffix += 1;
+    }
+    filename
+}
+
+/// Write a changeset releasing each package by `release`, which must be a
+/// major, minor or patch release, returning the path of the new changeset
+/// relative to the monorepo root.
+pub fn add<P>(
+    root: P,
+    package_names: &[String],
+    release: ReleaseType,
+    summary: &str,
+) -> Result<PathBuf, ChangesetError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(
+        root: &Path,
+        package_names: &[String],
+        release: ReleaseType,
+        summary: &str,
+    ) -> Result<PathBuf, ChangesetError> {
+        if release == ReleaseType::Prerelease {
+            return Err(ChangesetError {
+                kind: ChangesetErrorKind::UnsupportedRelease(release),
+            });
+        }
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        if let Some(package_name) = package_names
+            .iter()
+            .find(|package_name| !package_manifests_by_package_name.contains_key(*package_name))
+        {
+            return Err(ChangesetError {
+                kind: ChangesetErrorKind::UnknownPackage(package_name.to_owned()),
+            });
+        }
+
+        let directory = root.join(CHANGESET_DIRECTORY);
+        fs::create_dir_all(&directory).map_err(|err| ChangesetError {
+            kind: ChangesetErrorKind::Io {
+                path: directory.to_owned(),
+                err,
+            },
+        })?;
+        let filename = changeset_filename(&directory, summary);
+        let path = filename
+            .strip_prefix(root)
+            .expect("Changeset is not in the monorepo root")
+            .to_owned();
+        let changeset = Changeset {
+            path: path.to_owned(),
+            releases: package_names
+                .iter()
+                .map(|package_name| (package_name.to_owned(), release))
+                .collect(),
+            summary: summary.trim().to_owned(),
+        };
+        fs::write(&filename, changeset.to_string()).map_err(|err| ChangesetError {
+            kind: ChangesetErrorKind::Io {
+                path: filename.to_owned(),
+                err,
+            },
+        })?;
+        Ok(path)
+    }
+    inner(root.as_ref(), package_names, release, summary)
+}
+
+/// Read every changeset in the monorepo, sorted by path.
+pub fn read<P>(root: P) -> Result<Vec<Changeset>, ChangesetError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<Vec<Changeset>, ChangesetError> {
+        let directory = root.join(CHANGESET_DIRECTORY);
+        if !directory.is_dir() {
+            return Ok(Vec::new());
+        }
+        let io_error = |err| ChangesetError {
+            kind: ChangesetErrorKind::Io {
+                path: directory.to_owned(),
+                err,
+            },
+        };
+        let mut filenames = Vec::new();
+        for entry in fs::read_dir(&directory).map_err(io_error)? {
+            let filename = entry.map_err(io_error)?.path();
+            let is_changeset = filename
+                .extension()
+                .is_some_and(|extension| extension == "md")
+                && filename.file_name().is_some_and(|name| name != "README.md");
+            if is_changeset {
+                filenames.push(filename);
+            }
+        }
+        filenames.sort_unstable();
+
+        filenames
+            .into_iter()
+            .map(|filename| {
+                let contents = read_to_string(&filename)?;
+                let path = Path::new(CHANGESET_DIRECTORY).join(
+                    filename
+                        .file_name()
+                        .expect("Changeset filename is missing a basename"),
+                );
+                Changeset::parse(path, &contents)
+            })
+            .collect()
+    }
+    inner(root.as_ref())
+}
+
+/// The result of applying changesets.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct ChangesetPlan {
+    pub versions: VersionPlan,
+    pub changelogs: Changelogs,
+    /// Consumed changesets, relative to the monorepo root.
+    pub changesets: Vec<PathBuf>,
+}
+
+impl Display for ChangesetPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.versions)?;
+        writeln!(f, "Changelogs:")?;
+        for changelog in self.changelogs.iter() {
+            writeln!(f, "\t{}", changelog.path.display())?;
+        }
+        writeln!(f, "Changesets:")?;
+        for changeset in self.changesets.iter() {
+            writeln!(f, "\t{}", changeset.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// Format a summary as a changelog entry, indenting continuation lines to
+/// nest them under the list item.
+fn changelog_entry(summary: &str) -> String {
+    summary
+        .lines()
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => line.to_owned(),
+            _ if line.is_empty() => String::new(),
+            _ => format!("  {}", line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+struct PlannedChangesets {
+    plan: ChangesetPlan,
+    package_manifests_by_package_name: HashMap<String, PackageManifest>,
+    modified_package_names: Vec<String>,
+}
+
+fn plan_internal(root: &Path) -> Result<PlannedChangesets, ChangesetError> {
+    let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let configuration = MonorepoConfiguration::from_directory(root)?;
+    let mut package_manifests_by_package_name =
+        lerna_manifest.package_manifests_by_package_name()?;
+    let changesets = read(root)?;
+
+    // The release of each package, and the dependency it cascaded from
+    let mut releases: BTreeMap<&str, (ReleaseType, Option<&str>)> = BTreeMap::new();
+    let mut summaries: HashMap<&str, BTreeMap<u8, Vec<String>>> = HashMap::new();
+    for changeset in changesets.iter() {
+        for (package_name, release) in changeset.releases.iter() {
+            let package_name = package_manifests_by_package_name
+                .get_key_value(package_name)
+                .map(|(package_name, _)| package_name.as_str())
+                .ok_or_else(|| ChangesetError {
+                    kind: ChangesetErrorKind::UnknownPackage(package_name.to_owned()),
+                })?;
+            let entry = releases.entry(package_name).or_insert((*release, None));
+            if release_precedence(*release) > release_precedence(entry.0) {
+                entry.0 = *release;
+            }
+            if !changeset.summary.is_empty() {
+                summaries
+                    .entry(package_name)
+                    .or_default()
+                    .entry(release_precedence(*release))
+                    .or_default()
+                    .push(changelog_entry(&changeset.summary));
+            }
+        }
+    }
+    let dependents = internal_dependents(&package_manifests_by_package_name);
+    cascade_releases(&dependents, &mut releases);
+
+    let mut changes = Vec::new();
+    let mut versions = BTreeMap::new();
+    for (package_name, (release, cascaded_from)) in releases.iter() {
+        let package_manifest = &package_manifests_by_package_name[*package_name];
+        let current =
+            Version::parse(&package_manifest.contents.version).map_err(|err| ChangesetError {
+                kind: ChangesetErrorKind::InvalidVersion(package_name.to_string(), err),
+            })?;
+        let next = current.increment(*release, None);
+        changes.push(VersionChange {
+            name: package_name.to_string(),
+            current: current.to_string(),
+            next: next.to_string(),
+            cascaded_from: cascaded_from.map(ToOwned::to_owned),
+        });
+        versions.insert(package_name.to_string(), next);
+    }
+
+    let mut changelogs = Vec::new();
+    for (package_name, version) in versions.iter() {
+        let package_manifest = &package_manifests_by_package_name[package_name];
+        let groups: Vec<ChangelogGroup> = summaries
+            .remove(package_name.as_str())
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .map(|(precedence, entries)| ChangelogGroup {
+                title: match precedence {
+                    3 => "Major Changes",
+                    2 => "Minor Changes",
+                    _ => "Patch Changes",
+                },
+                entries,
+            })
+            .collect();
+        let mut updated_dependencies: Vec<String> = package_manifest
+            .internal_dependencies_iter(&package_manifests_by_package_name)
+            .filter_map(|dependency| {
+                versions
+                    .get(&dependency.contents.name)
+                    .map(|version| format!("{}@{}", dependency.contents.name, version))
+            })
+            .collect();
+        updated_dependencies.sort_unstable();
+        updated_dependencies.dedup();
+        if let Some(changelog) = package_changelog(
+            root,
+            package_manifest,
+            &version.to_string(),
+            &groups,
+            &updated_dependencies,
+        )? {
+            changelogs.push(changelog);
+        }
+    }
+
+    let modified_package_names = apply_versions(
+        root,
+        &configuration,
+        &mut package_manifests_by_package_name,
+        &versions,
+    )?;
+    let mut modified_package_names: Vec<String> = modified_package_names.into_iter().collect();
+    modified_package_names.sort_unstable();
+    let files = modified_package_names
+        .iter()
+        .map(|package_name| package_manifests_by_package_name[package_name].path())
+        .collect();
+
+    Ok(PlannedChangesets {
+        plan: ChangesetPlan {
+            versions: VersionPlan { changes, files },
+            changelogs: Changelogs::new(changelogs),
+            changesets: changesets
+                .into_iter()
+                .map(|changeset| changeset.path)
+                .collect(),
+        },
+        package_manifests_by_package_name,
+        modified_package_names,
+    })
+}
+
+/// Compute the versions and changelog sections the changesets call for,
+/// without writing any files. Internal packages depending on a released
+/// package receive a patch release so they can be pinned to the new
+/// version.
+pub fn plan<P>(root: P) -> Result<ChangesetPlan, ChangesetError>
+where
+    P: AsRef<Path>,
+{
+    Ok(plan_internal(root.as_ref())?.plan)
+}
+
+/// Apply the changesets as in `plan`: bump versions, pin internal
+/// dependents to them, prepend changelog sections and remove the consumed
+/// changesets. Package manifests and changelogs are written together, as in
+/// `configuration_file::write_all`, and the changesets are only removed once
+/// they are. Should removing a changeset fail, it would be applied again by
+/// the next run, so remove it by hand.
+pub fn apply<P>(root: P) -> Result<ChangesetPlan, ChangesetError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<ChangesetPlan, ChangesetError> {
+        let PlannedChangesets {
+            plan,
+            mut package_manifests_by_package_name,
+            modified_package_names,
+        } = plan_internal(root)?;
+        let changelogs = changelog_contents(root, &plan.changelogs)?;
+        write_all_with_text(
+            root,
+            modified_package_names
+                .iter()
+                .filter_map(|package_name| package_manifests_by_package_name.remove(package_name)),
+            changelogs,
+        )?;
+        for changeset in plan.changesets.iter() {
+            let filename = root.join(changeset);
+            fs::remove_file(&filename).map_err(|err| ChangesetError {
+                kind: ChangesetErrorKind::Io {
+                    path: filename,
+                    err,
+                },
+            })?;
+        }
+        Ok(plan)
+    }
+    inner(root.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::Changeset;
+    use crate::semver::ReleaseType;
+
+    #[test]
+    fn should_parse_changesets() {
+        let changeset = Changeset::parse(
+            PathBuf::from(".changeset/a.md"),
+            "---\n\"@scope/a\": minor\n'b': patch\n---\n\nAdd an option\n",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![("@scope/a", ReleaseType::Minor), ("b", ReleaseType::Patch)],
+            changeset
+                .releases
+                .iter()
+                .map(|(name, release)| (name.as_str(), *release))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!("Add an option", changeset.summary);
+        assert_eq!(
+            changeset,
+            Changeset::parse(changeset.path.to_owned(), &changeset.to_string()).unwrap()
+        );
+
+        let empty = Changeset::parse(PathBuf::from(".changeset/b.md"), "---\n---\n").unwrap();
+        assert!(empty.releases.is_empty());
+
+        assert!(Changeset::parse(PathBuf::from("c.md"), "no front matter").is_err());
+        assert!(Changeset::parse(PathBuf::from("c.md"), "---\na: huge\n---\n").is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use std::{
     fmt::Display,
-    fs::File,
+    fs::{self, File},
     io::{self, BufWriter, Write},
+    iter,
     path::{Path, PathBuf},
 };
 
@@ -28,6 +29,7 @@ impl std::error::Error for WriteError {
             WriteErrorKind::OpenFile(err) => Some(err),
             WriteErrorKind::Serialize(err) => Some(err),
             WriteErrorKind::Stream(err) => Some(err),
+            WriteErrorKind::Rename(err) => Some(err),
         }
     }
 }
@@ -37,6 +39,7 @@ pub enum WriteErrorKind {
     OpenFile(io::Error),
     Serialize(serde_json::Error),
     Stream(io::Error),
+    Rename(io::Error),
 }
 
 // REFACTOR: most of this impl is the same across all types
@@ -65,20 +68,167 @@ pub trait ConfigurationFile: Sized {
         configuration_file: impl ConfigurationFile,
     ) -> Result<(), WriteError> {
         let filename = monorepo_root.join(configuration_file.path());
-        let file = File::create(filename.clone()).map_err(|err| WriteError {
-            path: filename.clone(),
-            kind: WriteErrorKind::OpenFile(err),
-        })?;
-        let mut writer = BufWriter::new(file);
-        (|| {
-            let s = serde_json::to_string_pretty(configuration_file.contents())
-                .map_err(WriteErrorKind::Serialize)?;
-            writeln!(writer, "{}", s).map_err(WriteErrorKind::Stream)
-        })()
-        .map_err(|kind| WriteError {
-            path: filename,
-            kind,
-        })?;
+        write_contents(&filename, configuration_file.contents())
+    }
+}
+
+fn write_contents(filename: &Path, contents: &impl Serialize) -> Result<(), WriteError> {
+    let file = File::create(filename).map_err(|err| WriteError {
+        path: filename.to_owned(),
+        kind: WriteErrorKind::OpenFile(err),
+    })?;
+    let mut writer = BufWriter::new(file);
+    (|| {
+        let s = serde_json::to_string_pretty(contents).map_err(WriteErrorKind::Serialize)?;
+        writeln!(writer, "{}", s).map_err(WriteErrorKind::Stream)?;
+        writer.flush().map_err(WriteErrorKind::Stream)
+    })()
+    .map_err(|kind| WriteError {
+        path: filename.to_owned(),
+        kind,
+    })
+}
+
+/// Write several configuration files, first beside their originals, which
+/// are only replaced once every file is written. No original is modified
+/// when a file cannot be written. Replacing the originals is not atomic as a
+/// whole: when one cannot be replaced, those before it already are, and the
+/// remaining temporary files are removed.
+pub fn write_all<C>(
+    monorepo_root: &Path,
+    configuration_files: impl IntoIterator<Item = C>,
+) -> Result<(), WriteError>
+where
+    C: ConfigurationFile,
+{
+    write_all_with_text(monorepo_root, configuration_files, iter::empty())
+}
+
+/// Write several text files with the same guarantees as `write_all`.
+pub(crate) fn write_all_text(
+    files: impl IntoIterator<Item = (PathBuf, String)>,
+) -> Result<(), WriteError> {
+    replace_all(files.into_iter().map(|(filename, contents)| {
+        let write = move |temporary_filename: &Path| write_text(temporary_filename, contents);
+        (filename, write)
+    }))
+}
+
+/// Write configuration files and text files together, with the same
+/// guarantees as `write_all`.
+pub(crate) fn write_all_with_text<C>(
+    monorepo_root: &Path,
+    configuration_files: impl IntoIterator<Item = C>,
+    text_files: impl IntoIterator<Item = (PathBuf, String)>,
+) -> Result<(), WriteError>
+where
+    C: ConfigurationFile,
+{
+    type Write<'a> = Box<dyn FnOnce(&Path) -> Result<(), WriteError> + 'a>;
+    let configuration_files =
+        configuration_files
+            .into_iter()
+            .map(|configuration_file| -> (PathBuf, Write) {
+                let filename = monorepo_root.join(configuration_file.path());
+                let write = move |temporary_filename: &Path| {
+                    write_contents(temporary_filename, configuration_file.contents())
+                };
+                (filename, Box::new(write))
+            });
+    let text_files = text_files
+        .into_iter()
+        .map(|(filename, contents)| -> (PathBuf, Write) {
+            let write = move |temporary_filename: &Path| write_text(temporary_filename, contents);
+            (filename, Box::new(write))
+        });
+    replace_all(configuration_files.chain(text_files))
+}
+
+fn write_text(filename: &Path, contents: String) -> Result<(), WriteError> {
+    fs::write(filename, contents).map_err(|err| WriteError {
+        path: filename.to_owned(),
+        kind: WriteErrorKind::Stream(err),
+    })
+}
+
+/// Write each file beside its original with `write`, then replace the
+/// originals once every file is written.
+fn replace_all<F>(files: impl IntoIterator<Item = (PathBuf, F)>) -> Result<(), WriteError>
+where
+    F: FnOnce(&Path) -> Result<(), WriteError>,
+{
+    let mut written: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for (filename, write) in files {
+        let mut temporary_filename = filename.clone().into_os_string();
+        temporary_filename.push(".tmp");
+        let temporary_filename = PathBuf::from(temporary_filename);
+        if let Err(err) = write(&temporary_filename) {
+            // Leave no partial writes behind
+            for (temporary_filename, _) in written {
+                let _ = fs::remove_file(temporary_filename);
+            }
+            let _ = fs::remove_file(&temporary_filename);
+            return Err(err);
+        }
+        written.push((temporary_filename, filename));
+    }
+    let mut written = written.into_iter();
+    while let Some((temporary_filename, filename)) = written.next() {
+        if let Err(err) = fs::rename(&temporary_filename, &filename) {
+            let _ = fs::remove_file(&temporary_filename);
+            for (temporary_filename, _) in written {
+                let _ = fs::remove_file(temporary_filename);
+            }
+            return Err(WriteError {
+                path: filename,
+                kind: WriteErrorKind::Rename(err),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use tempdir::TempDir;
+
+    use super::{write_all, ConfigurationFile, WriteErrorKind};
+    use crate::package_manifest::PackageManifest;
+
+    #[test]
+    fn should_remove_temporary_files_when_a_rename_fails() -> Result<(), Box<dyn std::error::Error>>
+    {
+        // Arrange
+        let root = TempDir::new("typescript-tools-test-write-all")?;
+        let root = root.path();
+        for package in ["a", "b"] {
+            fs::create_dir(root.join(package))?;
+            fs::write(
+                root.join(package).join("package.json"),
+                format!("{{\"name\": \"{}\", \"version\": \"1.0.0\"}}\n", package),
+            )?;
+        }
+        let mut package_manifests = Vec::new();
+        for package in ["a", "b"] {
+            let mut package_manifest = PackageManifest::from_directory(root, Path::new(package))?;
+            package_manifest.contents.version = String::from("2.0.0");
+            package_manifests.push(package_manifest);
+        }
+        // A non-empty directory cannot be replaced by a file
+        fs::remove_file(root.join("b/package.json"))?;
+        fs::create_dir_all(root.join("b/package.json/nested"))?;
+
+        // Act
+        let err = write_all(root, package_manifests).unwrap_err();
+
+        // Assert
+        assert!(matches!(err.kind, WriteErrorKind::Rename(_)));
+        assert!(fs::read_to_string(root.join("a/package.json"))?.contains("2.0.0"));
+        assert!(!root.join("a/package.json.tmp").exists());
+        assert!(!root.join("b/package.json.tmp").exists());
         Ok(())
     }
 }
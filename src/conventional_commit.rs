@@ -0,0 +1,109 @@
+//! Commit messages following the [Conventional Commits] specification.
+//!
+//! [Conventional Commits]: https://www.conventionalcommits.org/en/v1.0.0/
+
+use crate::semver::ReleaseType;
+
+/// The parsed header of a conventional commit, such as
+/// `feat(parser)!: support arrays`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ConventionalCommit {
+    /// The commit type, such as `feat` or `fix`, in lowercase.
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    /// Parse a commit subject and body, returning `None` when the subject
+    /// is not a conventional commit header.
+    pub fn parse(subject: &str, body: &str) -> Option<Self> {
+        let (header, description) = subject.split_once(':')?;
+        let description = description.trim();
+        let (header, bang) = match header.strip_suffix('!') {
+            Some(header) => (header, true),
+            None => (header, false),
+        };
+        let (kind, scope) = match header.split_once('(') {
+            Some((kind, scope)) => (kind, Some(scope.strip_suffix(')')?)),
+            None => (header, None),
+        };
+        let is_word = |s: &str| {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        };
+        if !is_word(kind) || scope.is_some_and(|scope| scope.trim().is_empty()) {
+            return None;
+        }
+        if description.is_empty() {
+            return None;
+        }
+
+        let breaking = bang
+            || body.lines().any(|line| {
+                line.starts_with("BREAKING CHANGE:") || line.starts_with("BREAKING-CHANGE:")
+            });
+        Some(Self {
+            kind: kind.to_ascii_lowercase(),
+            scope: scope.map(|scope| scope.trim().to_owned()),
+            breaking,
+            description: description.to_owned(),
+        })
+    }
+
+    /// The release this commit calls for: a major release for breaking
+    /// changes, a minor release for features and a patch release for fixes
+    /// and performance improvements.
+    pub fn release_type(&self) -> Option<ReleaseType> {
+        match self.kind.as_str() {
+            _ if self.breaking => Some(ReleaseType::Major),
+            "feat" => Some(ReleaseType::Minor),
+            "fix" | "perf" => Some(ReleaseType::Patch),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConventionalCommit;
+    use crate::semver::ReleaseType;
+
+    fn release_type(subject: &str, body: &str) -> Option<ReleaseType> {
+        ConventionalCommit::parse(subject, body).and_then(|commit| commit.release_type())
+    }
+
+    #[test]
+    fn should_parse_header() {
+        let commit = ConventionalCommit::parse("feat(parser)!: support arrays", "").unwrap();
+        assert_eq!("feat", commit.kind);
+        assert_eq!(Some("parser".to_owned()), commit.scope);
+        assert!(commit.breaking);
+        assert_eq!("support arrays", commit.description);
+
+        assert_eq!(None, ConventionalCommit::parse("Merge branch 'main'", ""));
+        assert_eq!(None, ConventionalCommit::parse("fix(: oops", ""));
+        assert_eq!(None, ConventionalCommit::parse("fix:", ""));
+        assert_eq!(None, ConventionalCommit::parse("not a type: words", ""));
+    }
+
+    #[test]
+    fn should_infer_release_types() {
+        assert_eq!(
+            Some(ReleaseType::Major),
+            release_type("feat!: drop node 14", "")
+        );
+        assert_eq!(
+            Some(ReleaseType::Major),
+            release_type("refactor: rename", "BREAKING CHANGE: renamed export")
+        );
+        assert_eq!(Some(ReleaseType::Minor), release_type("feat: add flag", ""));
+        assert_eq!(Some(ReleaseType::Patch), release_type("fix(cli): typo", ""));
+        assert_eq!(Some(ReleaseType::Patch), release_type("perf: faster", ""));
+        assert_eq!(None, release_type("chore: bump deps", ""));
+        assert_eq!(None, release_type("update readme", ""));
+    }
+}
@@ -0,0 +1,156 @@
+//! Read history from the local git repository by invoking `git`.
+
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct GitError {
+    pub kind: GitErrorKind,
+}
+
+impl Display for GitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            GitErrorKind::Spawn(_) => write!(f, "unable to run git"),
+            GitErrorKind::Failed { args, stderr } => {
+                write!(f, "git {} failed: {}", args.join(" "), stderr.trim_end())
+            }
+        }
+    }
+}
+
+impl std::error::Error for GitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            GitErrorKind::Spawn(err) => Some(err),
+            GitErrorKind::Failed { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum GitErrorKind {
+    #[non_exhaustive]
+    Spawn(io::Error),
+    #[non_exhaustive]
+    Failed { args: Vec<String>, stderr: String },
+}
+
+/// A commit and the files it changed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Commit {
+    pub hash: String,
+    pub subject: String,
+    pub body: String,
+    /// Changed files, relative to the directory git was run in.
+    pub files: Vec<PathBuf>,
+}
+
+impl Commit {
+    /// The abbreviated commit hash.
+    pub fn short_hash(&self) -> &str {
+        &self.hash[..self.hash.len().min(7)]
+    }
+}
+
+fn git(directory: &Path, args: &[&str]) -> Result<String, GitError> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .map_err(|err| GitError {
+            kind: GitErrorKind::Spawn(err),
+        })?;
+    if !output.status.success() {
+        return Err(GitError {
+            kind: GitErrorKind::Failed {
+                args: args.iter().map(ToString::to_string).collect(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            },
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The contents of a file at a revision, or `None` when the file does not
+/// exist at that revision. The path is relative to `directory`.
+pub fn show(directory: &Path, revision: &str, path: &Path) -> Result<Option<String>, GitError> {
+    let path = path.to_string_lossy();
+    // Lists nothing for a missing file, but fails for an unknown revision
+    let listing = git(
+        directory,
+        &["ls-tree", "--name-only", revision, "--", &path],
+    )?;
+    if listing.trim().is_empty() {
+        return Ok(None);
+    }
+    let object = format!("{}:./{}", revision, path);
+    git(directory, &["show", &object]).map(Some)
+}
+
+/// Tags reachable from `HEAD`.
+pub fn tags(directory: &Path) -> Result<Vec<String>, GitError> {
+    let output = git(directory, &["tag", "--list", "--merged", "HEAD"])?;
+    Ok(output.lines().map(ToOwned::to_owned).collect())
+}
+
+/// Non-merge commits reachable from `HEAD` but not from `since`, newest
+/// first, changing files under `pathspec`, relative to `directory`. Without
+/// `since`, every commit reachable from `HEAD` is returned. Files are listed
+/// relative to `directory`.
+pub fn commits(
+    directory: &Path,
+    since: Option<&str>,
+    pathspec: &Path,
+) -> Result<Vec<Commit>, GitError> {
+    let range = match since {
+        Some(since) => format!("{}..HEAD", since),
+        None => "HEAD".to_owned(),
+    };
+    let pathspec = match pathspec.as_os_str().is_empty() {
+        true => Cow::Borrowed("."),
+        false => pathspec.to_string_lossy(),
+    };
+    let output = git(
+        directory,
+        &[
+            "log",
+            "--no-merges",
+            "--relative",
+            "--name-only",
+            "--format=%x1e%H%x1f%s%x1f%b%x1f",
+            &range,
+            "--",
+            &pathspec,
+        ],
+    )?;
+
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.splitn(4, '\x1f');
+            let hash = fields.next()?.trim();
+            let subject = fields.next()?;
+            let body = fields.next()?;
+            let files = fields.next()?;
+            Some(Commit {
+                hash: hash.to_owned(),
+                subject: subject.to_owned(),
+                body: body.trim().to_owned(),
+                files: files
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from)
+                    .collect(),
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,488 @@
+//! Extraction of module specifiers from TypeScript sources, without a full
+//! parser: comments, strings, template literals and regular expressions are
+//! skipped so that only real `import`, `export ... from`, `import()` and
+//! `require()` statements are found.
+
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use globwalk::{FileType, GlobWalkerBuilder};
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::{read_to_string, FromFileError};
+use crate::package_manifest::PackageManifest;
+
+/// TypeScript sources of a package, excluding declaration files and
+/// installed dependencies.
+const SOURCE_PATTERNS: &[&str] = &[
+    "**/*.{ts,tsx,mts,cts}",
+    "!**/*.d.{ts,mts,cts}",
+    "!**/node_modules",
+];
+
+/// Modules built into Node.js, which are never declared as dependencies.
+const NODE_BUILTIN_MODULES: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/// Keywords after which a `/` starts a regular expression rather than a
+/// division.
+const KEYWORDS_PRECEDING_EXPRESSION: &[&str] = &[
+    "return",
+    "typeof",
+    "instanceof",
+    "in",
+    "of",
+    "new",
+    "delete",
+    "void",
+    "throw",
+    "case",
+    "do",
+    "else",
+    "yield",
+    "await",
+];
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ScanImportsError {
+    pub kind: ScanImportsErrorKind,
+}
+
+impl Display for ScanImportsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unable to scan package sources for imports")
+    }
+}
+
+impl std::error::Error for ScanImportsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ScanImportsErrorKind::GlobWalkBuilder(err) => Some(err),
+            ScanImportsErrorKind::GlobWalk(err) => Some(err),
+            ScanImportsErrorKind::FromFile(err) => Some(err),
+        }
+    }
+}
+
+impl From<globwalk::GlobError> for ScanImportsError {
+    fn from(err: globwalk::GlobError) -> Self {
+        Self {
+            kind: ScanImportsErrorKind::GlobWalkBuilder(err),
+        }
+    }
+}
+
+impl From<globwalk::WalkError> for ScanImportsError {
+    fn from(err: globwalk::WalkError) -> Self {
+        Self {
+            kind: ScanImportsErrorKind::GlobWalk(err),
+        }
+    }
+}
+
+impl From<FromFileError> for ScanImportsError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ScanImportsErrorKind::FromFile(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ScanImportsErrorKind {
+    #[non_exhaustive]
+    GlobWalkBuilder(globwalk::GlobError),
+    #[non_exhaustive]
+    GlobWalk(globwalk::WalkError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+}
+
+/// A module specifier found in a source file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ImportSpecifier {
+    /// The imported module, such as `react`, `@scope/package/feature` or
+    /// `./local`.
+    pub specifier: String,
+    /// 1-based line of the specifier.
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token<'a> {
+    Identifier(&'a str),
+    /// A string literal, or a template literal without substitutions.
+    String(String),
+    Punctuator(char),
+    /// Any other value, such as a number or a regular expression.
+    Other,
+}
+
+impl<'a> Token<'a> {
+    /// Whether a `/` following this token starts a regular expression.
+    fn precedes_expression(&self) -> bool {
+        match self {
+            Token::Identifier(identifier) => KEYWORDS_PRECEDING_EXPRESSION.contains(identifier),
+            Token::Punctuator(punctuator) => !matches!(punctuator, ')' | ']' | '}'),
+            Token::String(_) | Token::Other => false,
+        }
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+/// Split source text into tokens, each with its 1-based line.
+fn tokenize(contents: &str) -> Vec<(Token<'_>, usize)> {
+    let chars: Vec<(usize, char)> = contents.char_indices().collect();
+    let at = |index: usize| chars.get(index).map(|(_, c)| *c);
+    let mut tokens: Vec<(Token, usize)> = Vec::new();
+    let mut line = 1;
+    let mut index = 0;
+
+    while let Some(c) = at(index) {
+        let start_line = line;
+        match c {
+            '\n' => {
+                line += 1;
+                index += 1;
+            }
+            c if c.is_whitespace() => index += 1,
+            '/' if at(index + 1) == Some('/') => {
+                while at(index).is_some_and(|c| c != '\n') {
+                    index += 1;
+                }
+            }
+            '/' if at(index + 1) == Some('*') => {
+                index += 2;
+                while let Some(c) = at(index) {
+                    if c == '*' && at(index + 1) == Some('/') {
+                        index += 2;
+                        break;
+                    }
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    index += 1;
+                }
+            }
+            '\'' | '"' => {
+                let quote = c;
+                let mut value = String::new();
+                index += 1;
+                while let Some(c) = at(index) {
+                    index += 1;
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = at(index) {
+                                value.push(escaped);
+                                index += 1;
+                            }
+                        }
+                        '\n' => {
+                            line += 1;
+                            break;
+                        }
+                        c if c == quote => break,
+                        c => value.push(c),
+                    }
+                }
+                tokens.push((Token::String(value), start_line));
+            }
+            '`' => {
+                let mut value = String::new();
+                let mut has_substitution = false;
+                index += 1;
+                while let Some(c) = at(index) {
+                    index += 1;
+                    match c {
+                        '\\' => index += 1,
+                        '`' => break,
+                        '$' if at(index) == Some('{') => has_substitution = true,
+                        '\n' => {
+                            line += 1;
+                            value.push(c);
+                        }
+                        c => value.push(c),
+                    }
+                }
+                let token = match has_substitution {
+                    true => Token::Other,
+                    false => Token::String(value),
+                };
+                tokens.push((token, start_line));
+            }
+            '/' if tokens
+                .last()
+                .is_none_or(|(token, _)| token.precedes_expression()) =>
+            {
+                let mut in_class = false;
+                index += 1;
+                while let Some(c) = at(index) {
+                    index += 1;
+                    match c {
+                        '\\' => index += 1,
+                        '[' => in_class = true,
+                        ']' => in_class = false,
+                        '/' if !in_class => break,
+                        '\n' => {
+                            line += 1;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                tokens.push((Token::Other, start_line));
+            }
+            c if is_identifier_start(c) => {
+                let start = chars[index].0;
+                while at(index).is_some_and(is_identifier_part) {
+                    index += 1;
+                }
+                let end = chars
+                    .get(index)
+                    .map_or(contents.len(), |(offset, _)| *offset);
+                tokens.push((Token::Identifier(&contents[start..end]), start_line));
+            }
+            c if c.is_ascii_digit() => {
+                while at(index).is_some_and(|c| is_identifier_part(c) || c == '.') {
+                    index += 1;
+                }
+                tokens.push((Token::Other, start_line));
+            }
+            c => {
+                tokens.push((Token::Punctuator(c), start_line));
+                index += 1;
+            }
+        }
+    }
+    tokens
+}
+
+/// Find the module specifiers of every `import`, `export ... from`,
+/// `import()` and `require()` in TypeScript source text.
+pub fn scan(contents: &str) -> Vec<ImportSpecifier> {
+    let tokens = tokenize(contents);
+    let token = |index: usize| tokens.get(index).map(|(token, _)| token);
+    let mut specifiers = Vec::new();
+    let mut push = |index: usize| {
+        if let Some((Token::String(specifier), line)) = tokens.get(index) {
+            specifiers.push(ImportSpecifier {
+                specifier: specifier.to_owned(),
+                line: *line,
+            });
+        }
+    };
+
+    for (index, (current, _)) in tokens.iter().enumerate() {
+        // Skip property accesses, such as `module.require(...)`
+        if index > 0 && token(index - 1) == Some(&Token::Punctuator('.')) {
+            continue;
+        }
+        match current {
+            Token::Identifier("require") | Token::Identifier("import")
+                if token(index + 1) == Some(&Token::Punctuator('('))
+                    && matches!(token(index + 2), Some(Token::String(_))) =>
+            {
+                push(index + 2);
+            }
+            Token::Identifier("import") if matches!(token(index + 1), Some(Token::String(_))) => {
+                push(index + 1);
+            }
+            Token::Identifier("import" | "export") => {
+                let mut position = index + 1;
+                while let Some(next) = token(position) {
+                    match next {
+                        Token::Identifier("from")
+                            if matches!(token(position + 1), Some(Token::String(_))) =>
+                        {
+                            push(position + 1);
+                            break;
+                        }
+                        Token::Identifier("import" | "export")
+                        | Token::Punctuator(';' | '(' | '=' | '.') => break,
+                        _ => position += 1,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    specifiers
+}
+
+/// Every module specifier imported by the TypeScript sources of a package,
+/// paired with the path of the source file relative to the monorepo root
+/// and sorted by location.
+pub fn package_imports(
+    root: &Path,
+    package_manifest: &PackageManifest,
+) -> Result<Vec<(PathBuf, ImportSpecifier)>, ScanImportsError> {
+    let package_directory = root.join(package_manifest.directory());
+    let mut imports = Vec::new();
+    for dir_entry in GlobWalkerBuilder::from_patterns(&package_directory, SOURCE_PATTERNS)
+        .file_type(FileType::FILE)
+        .build()?
+    {
+        let dir_entry = dir_entry?;
+        let contents = read_to_string(dir_entry.path())?;
+        let file = package_manifest.directory().join(
+            dir_entry
+                .path()
+                .strip_prefix(&package_directory)
+                .unwrap_or_else(|_| dir_entry.path()),
+        );
+        imports.extend(
+            scan(&contents)
+                .into_iter()
+                .map(|import| (file.clone(), import)),
+        );
+    }
+    imports.sort_unstable_by(|a, b| (&a.0, a.1.line).cmp(&(&b.0, b.1.line)));
+    Ok(imports)
+}
+
+/// Name of the package a module specifier imports, such as `@scope/package`
+/// for `@scope/package/feature`, or `None` for relative paths, subpath
+/// imports and Node.js built-in modules.
+pub fn package_name(specifier: &str) -> Option<&str> {
+    if specifier.starts_with(['.', '/', '#', '~']) || specifier.contains(':') {
+        return None;
+    }
+    let mut segments = specifier.split('/');
+    let first = segments.next()?;
+    let length = match first.strip_prefix('@') {
+        Some(scope) => {
+            let name = segments.next()?;
+            if scope.is_empty() || name.is_empty() {
+                return None;
+            }
+            first.len() + 1 + name.len()
+        }
+        None => first.len(),
+    };
+    let name = &specifier[..length];
+    match name.is_empty() || NODE_BUILTIN_MODULES.contains(&name) {
+        true => None,
+        false => Some(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{package_name, scan};
+
+    #[test]
+    fn should_find_every_kind_of_import() {
+        let contents = r#"import React from "react";
+import type { Props } from '@scope/types';
+import './side-effect';
+export * from "./local";
+export { a, b as c } from "@scope/package/feature";
+const lazy = await import("lodash/fp");
+const fs = require('fs');
+import path = require("path");
+"#;
+        assert_eq!(
+            vec![
+                ("react", 1),
+                ("@scope/types", 2),
+                ("./side-effect", 3),
+                ("./local", 4),
+                ("@scope/package/feature", 5),
+                ("lodash/fp", 6),
+                ("fs", 7),
+                ("path", 8),
+            ],
+            scan(contents)
+                .iter()
+                .map(|import| (import.specifier.as_str(), import.line))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_skip_comments_strings_and_regular_expressions() {
+        let contents = r#"// import a from "commented";
+/* import b from "block"
+   comment */
+const message = "import c from 'in-string'";
+const template = `require("in-template")`;
+const pattern = /import d from "regex"/;
+export const e = 1;
+module.require("property");
+import { f } from "f";
+"#;
+        assert_eq!(
+            vec![("f", 9)],
+            scan(contents)
+                .iter()
+                .map(|import| (import.specifier.as_str(), import.line))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_map_specifiers_to_package_names() {
+        assert_eq!(Some("react"), package_name("react"));
+        assert_eq!(Some("lodash"), package_name("lodash/fp"));
+        assert_eq!(
+            Some("@scope/package"),
+            package_name("@scope/package/feature")
+        );
+        assert_eq!(None, package_name("./local"));
+        assert_eq!(None, package_name("node:fs"));
+        assert_eq!(None, package_name("fs/promises"));
+        assert_eq!(None, package_name("#internal"));
+        assert_eq!(None, package_name("@/alias"));
+    }
+}
@@ -1,5 +1,5 @@
 use std::fmt::Display;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::{fs, io};
 
 use serde::Deserialize;
@@ -39,12 +39,35 @@ where
     // Reading a file into a string before invoking Serde is faster than
     // invoking Serde from a BufReader, see
     // https://github.com/serde-rs/json/issues/160
-    let string = fs::read_to_string(filename).map_err(|err| FromFileError {
-        path: filename.to_owned(),
-        kind: FromFileErrorKind::ReadFile(err),
-    })?;
+    let string = read_to_string(filename)?;
     serde_json::from_str(&string).map_err(|err| FromFileError {
         path: filename.to_owned(),
         kind: FromFileErrorKind::Parse(err),
     })
 }
+
+pub(crate) fn read_to_string(filename: &Path) -> Result<String, FromFileError> {
+    fs::read_to_string(filename).map_err(|err| FromFileError {
+        path: filename.to_owned(),
+        kind: FromFileErrorKind::ReadFile(err),
+    })
+}
+
+/// Lexically normalize a path, resolving `.` and `..` components without
+/// touching the file-system.
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                _ => normalized.push(component),
+            },
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
@@ -4,13 +4,26 @@
 mod out_of_date_project_references;
 mod unpinned_dependencies;
 
+pub mod annotation;
+pub mod changelog;
+pub mod changeset;
 pub mod configuration_file;
+pub mod conventional_commit;
+pub mod git;
+pub mod import_specifiers;
 pub mod io;
 pub mod link;
 pub mod lint;
+pub mod lockfile;
 pub mod make_depend;
+pub mod monorepo_configuration;
 pub mod monorepo_manifest;
+pub mod package_exports;
 pub mod package_manifest;
 pub mod pin;
+pub mod publish;
 pub mod query;
+pub mod release;
+pub mod semver;
 pub mod typescript_config;
+pub mod version;
@@ -8,16 +8,20 @@ use pathdiff::diff_paths;
 
 use crate::configuration_file::{ConfigurationFile, WriteError};
 use crate::io::FromFileError;
+use crate::monorepo_configuration::{LinkConfiguration, MonorepoConfiguration};
 use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
 use crate::out_of_date_project_references::{
-    AllOutOfDateTypescriptConfig, OutOfDatePackageProjectReferences,
-    OutOfDateParentProjectReferences, OutOfDateTypescriptConfig,
+    OutOfDatePackageProjectReferences, OutOfDateParentProjectReferences,
 };
 use crate::package_manifest::PackageManifest;
 use crate::typescript_config::{
     TypescriptConfig, TypescriptParentProjectReference, TypescriptProjectReference,
 };
 
+pub use crate::out_of_date_project_references::{
+    AllOutOfDateTypescriptConfig, OutOfDateTypescriptConfig,
+};
+
 #[derive(Debug)]
 #[non_exhaustive]
 pub struct LinkError {
@@ -156,46 +160,44 @@ fn link_children_packages(
 fn link_package_dependencies(
     root: &Path,
     package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+    link_configuration: &LinkConfiguration,
 ) -> Result<(), LinkError> {
-    out_of_date_package_project_references(root, package_manifests_by_package_name)?
-        .map(
-            |maybe_package_project_references| -> Result<Option<_>, FromFileError> {
-                let OutOfDatePackageProjectReferences {
-                    mut tsconfig,
-                    desired_references,
-                } = maybe_package_project_references?;
-                // Compare the current references against the desired references
-                let current_project_references = &tsconfig
-                    .contents
-                    .get("references")
-                    .map(|value| {
-                        serde_json::from_value::<Vec<TypescriptProjectReference>>(value.clone())
-                            .expect("value starting as JSON should be deserializable")
-                    })
-                    .unwrap_or_default();
-
-                let needs_update = !current_project_references.eq(&desired_references);
-                if !needs_update {
-                    return Ok(None);
-                }
-
-                // Update the current tsconfig with the desired references
-                tsconfig.contents.insert(
-                    String::from("references"),
-                    serde_json::to_value(desired_references).expect(
-                        "should be able to express desired TypeScript project references as JSON",
-                    ),
-                );
-
-                Ok(Some(tsconfig))
-            },
-        )
-        .filter_map(Result::transpose)
-        .map(|maybe_tsconfig| -> Result<(), LinkError> {
-            let tsconfig = maybe_tsconfig?;
-            Ok(TypescriptConfig::write(root, tsconfig)?)
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+    out_of_date_package_project_references(
+        root,
+        package_manifests_by_package_name,
+        link_configuration,
+    )?
+    .map(
+        |maybe_package_project_references| -> Result<Option<_>, FromFileError> {
+            let OutOfDatePackageProjectReferences {
+                mut tsconfig,
+                desired_references,
+            } = maybe_package_project_references?;
+            // Compare the current references against the desired references
+            let current_project_references = &tsconfig.project_references();
+
+            let needs_update = !current_project_references.eq(&desired_references);
+            if !needs_update {
+                return Ok(None);
+            }
+
+            // Update the current tsconfig with the desired references
+            tsconfig.contents.insert(
+                String::from("references"),
+                serde_json::to_value(desired_references).expect(
+                    "should be able to express desired TypeScript project references as JSON",
+                ),
+            );
+
+            Ok(Some(tsconfig))
+        },
+    )
+    .filter_map(Result::transpose)
+    .map(|maybe_tsconfig| -> Result<(), LinkError> {
+        let tsconfig = maybe_tsconfig?;
+        Ok(TypescriptConfig::write(root, tsconfig)?)
+    })
+    .collect::<Result<Vec<_>, _>>()?;
     Ok(())
 }
 
@@ -205,10 +207,15 @@ where
 {
     fn inner(root: &Path) -> Result<(), LinkError> {
         let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let configuration = MonorepoConfiguration::from_directory(root)?;
         let package_manifests_by_package_name =
             lerna_manifest.package_manifests_by_package_name()?;
         link_children_packages(root, &package_manifests_by_package_name)?;
-        link_package_dependencies(root, &package_manifests_by_package_name)?;
+        link_package_dependencies(
+            root,
+            &package_manifests_by_package_name,
+            &configuration.link,
+        )?;
         // TODO(7): create `tsconfig.settings.json` files
         Ok(())
     }
@@ -284,7 +291,6 @@ pub enum LinkLintErrorKind {
     FromFile(FromFileError),
     #[non_exhaustive]
     InvalidUtf8(InvalidUtf8Error),
-    // TODO: augment this error with information for a useful error message
     #[non_exhaustive]
     ProjectReferencesOutOfDate(AllOutOfDateTypescriptConfig),
 }
@@ -302,7 +308,7 @@ fn out_of_date_parent_project_references<'a>(
         .into_iter()
         .map(move |(directory, children)| {
             let desired_references = create_project_references(children);
-            let tsconfig = TypescriptParentProjectReference::from_directory(&root, &directory)?;
+            let tsconfig = TypescriptParentProjectReference::from_directory(root, &directory)?;
             let current_project_references = &tsconfig.contents.references;
             let needs_update = !current_project_references.eq(&desired_references);
             Ok(match needs_update {
@@ -317,52 +323,92 @@ fn out_of_date_parent_project_references<'a>(
     Ok(iter)
 }
 
+fn create_package_project_references<I>(
+    tsconfig_directory: &Path,
+    referenced_directories: I,
+) -> Vec<TypescriptProjectReference>
+where
+    I: IntoIterator<Item = PathBuf>,
+{
+    let mut typescript_project_references: Vec<String> = referenced_directories
+        .into_iter()
+        .map(|directory| {
+            diff_paths(directory, tsconfig_directory)
+                .expect("Unable to calculate a relative path to dependency from package")
+                .to_str()
+                .expect("Path not valid UTF-8 encoded")
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    // REFACTOR: can drop a `collect` if we implement Ord on TypescriptProjectReference
+    typescript_project_references.sort_unstable();
+    // A dependency may be declared in more than one dependency group
+    typescript_project_references.dedup();
+
+    typescript_project_references
+        .into_iter()
+        .map(|path| TypescriptProjectReference { path })
+        .collect()
+}
+
 fn out_of_date_package_project_references<'a>(
     root: &'a Path,
     package_manifests_by_package_name: &'a HashMap<String, PackageManifest>,
+    link_configuration: &'a LinkConfiguration,
 ) -> Result<
     impl Iterator<Item = Result<OutOfDatePackageProjectReferences, FromFileError>> + 'a,
     InvalidUtf8Error,
 > {
     let iter = package_manifests_by_package_name
         .values()
-        .map(move |package_manifest| {
+        .flat_map(move |package_manifest| {
             let package_directory = package_manifest.directory();
-            let tsconfig = TypescriptConfig::from_directory(&root, &package_directory)?;
-            let internal_dependencies =
-                package_manifest.internal_dependencies_iter(&package_manifests_by_package_name);
-
-            let desired_references: Vec<TypescriptProjectReference> = {
-                let mut typescript_project_references: Vec<String> = internal_dependencies
-                    .into_iter()
-                    .map(|dependency| {
-                        diff_paths(dependency.directory(), package_manifest.directory())
-                            .expect(
-                                "Unable to calculate a relative path to dependency from package",
+            let main_project = (
+                package_directory.clone(),
+                create_package_project_references(
+                    &package_directory,
+                    package_manifest
+                        .internal_dependencies_iter(package_manifests_by_package_name)
+                        .map(ConfigurationFile::directory),
+                ),
+            );
+
+            // Secondary projects reference the package's main project and the
+            // internal dependencies from their configured dependency groups.
+            // Packages without the secondary tsconfig file are left alone.
+            let additional_projects = link_configuration
+                .additional_configs
+                .iter()
+                .filter_map(|additional_config| {
+                    let tsconfig_directory = package_directory.join(&additional_config.directory);
+                    if !root
+                        .join(&tsconfig_directory)
+                        .join(TypescriptConfig::FILENAME)
+                        .is_file()
+                    {
+                        return None;
+                    }
+                    let desired_references = create_package_project_references(
+                        &tsconfig_directory,
+                        package_manifest
+                            .internal_dependencies_in_groups_iter(
+                                &additional_config.dependency_groups,
+                                package_manifests_by_package_name,
                             )
-                            .to_str()
-                            .expect("Path not valid UTF-8 encoded")
-                            .to_string()
-                    })
-                    .collect::<Vec<_>>();
-                // REFACTOR: can drop a `collect` if we implement Ord on TypescriptProjectReference
-                typescript_project_references.sort_unstable();
-
-                typescript_project_references
-                    .into_iter()
-                    .map(|path| TypescriptProjectReference { path })
-                    .collect()
-            };
+                            .map(ConfigurationFile::directory)
+                            .chain(std::iter::once(package_directory.clone())),
+                    );
+                    Some((tsconfig_directory, desired_references))
+                })
+                .collect::<Vec<_>>();
+
+            std::iter::once(main_project).chain(additional_projects)
+        })
+        .map(move |(tsconfig_directory, desired_references)| {
+            let tsconfig = TypescriptConfig::from_directory(root, &tsconfig_directory)?;
 
             // Compare the current references against the desired references
-            let current_project_references = &tsconfig
-                .contents
-                .get("references")
-                .map(|value| {
-                    serde_json::from_value::<Vec<TypescriptProjectReference>>(value.clone())
-                        .expect("value starting as JSON should be serializable")
-                })
-                .unwrap_or_default();
+            let current_project_references = &tsconfig.project_references();
 
             let needs_update = !current_project_references.eq(&desired_references);
             Ok(match needs_update {
@@ -378,12 +424,15 @@ fn out_of_date_package_project_references<'a>(
     Ok(iter)
 }
 
-pub fn lint<P>(root: P) -> Result<(), LinkLintError>
+/// Enumerate every tsconfig file whose project references do not match the
+/// monorepo's internal dependency graph.
+pub fn lint_report<P>(root: P) -> Result<AllOutOfDateTypescriptConfig, LinkLintError>
 where
     P: AsRef<Path>,
 {
-    fn inner(root: &Path) -> Result<(), LinkLintError> {
+    fn inner(root: &Path) -> Result<AllOutOfDateTypescriptConfig, LinkLintError> {
         let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let configuration = MonorepoConfiguration::from_directory(root)?;
         let package_manifests_by_package_name =
             lerna_manifest.package_manifests_by_package_name()?;
 
@@ -394,21 +443,31 @@ where
                 },
             );
 
-        let is_dependencies_link_success =
-            out_of_date_package_project_references(root, &package_manifests_by_package_name)?.map(
-                |result| -> Result<OutOfDateTypescriptConfig, FromFileError> {
-                    result.map(Into::into)
-                },
-            );
+        let is_dependencies_link_success = out_of_date_package_project_references(
+            root,
+            &package_manifests_by_package_name,
+            &configuration.link,
+        )?
+        .map(
+            |result| -> Result<OutOfDateTypescriptConfig, FromFileError> { result.map(Into::into) },
+        );
 
         let lint_issues: AllOutOfDateTypescriptConfig = is_children_link_success
             .chain(is_dependencies_link_success)
             .collect::<Result<_, _>>()?;
 
-        match lint_issues.is_empty() {
-            true => Ok(()),
-            false => Err(lint_issues)?,
-        }
+        Ok(lint_issues)
     }
     inner(root.as_ref())
 }
+
+pub fn lint<P>(root: P) -> Result<(), LinkLintError>
+where
+    P: AsRef<Path>,
+{
+    let lint_issues = lint_report(root)?;
+    match lint_issues.is_empty() {
+        true => Ok(()),
+        false => Err(lint_issues)?,
+    }
+}
@@ -1,10 +1,27 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 
-use crate::configuration_file::ConfigurationFile;
+use serde::{Deserialize, Serialize};
+
+use crate::configuration_file::{ConfigurationFile, WriteError};
 use crate::io::FromFileError;
+use crate::monorepo_configuration::MonorepoConfiguration;
 use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::{DependencyGroup, DependencySpecifier, PackageManifest};
+use crate::semver::{split_operator, Range, Version};
+
+pub mod boundaries;
+pub mod deep_imports;
+pub mod entry_points;
+pub mod exports;
+pub mod imports;
+pub mod lockfile;
+pub mod peer_dependencies;
+pub mod tsconfig;
+pub mod version_policy;
+
+use version_policy::{apply_policy, policy_violations, PolicyViolation};
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -24,6 +41,10 @@ impl Display for LintError {
             LintErrorKind::InvalidUtf8(path) => {
                 write!(f, "path cannot be expressed as UTF-8: {:?}", path)
             }
+            LintErrorKind::ExplicitVersionWithoutDependencies => write!(
+                f,
+                "an explicit version can only be written to specified dependencies"
+            ),
             _ => write!(f, "error linting dependency versions"),
         }
     }
@@ -34,9 +55,11 @@ impl std::error::Error for LintError {
         match &self.kind {
             LintErrorKind::EnumeratePackageManifests(err) => Some(err),
             LintErrorKind::FromFile(err) => Some(err),
+            LintErrorKind::Write(err) => Some(err),
             LintErrorKind::UnknownDependency(_) => None,
             LintErrorKind::UnexpectedInternalDependencyVersion => None,
             LintErrorKind::InvalidUtf8(_) => None,
+            LintErrorKind::ExplicitVersionWithoutDependencies => None,
         }
     }
 }
@@ -48,12 +71,18 @@ pub enum LintErrorKind {
     #[non_exhaustive]
     EnumeratePackageManifests(EnumeratePackageManifestsError),
     #[non_exhaustive]
+    Write(WriteError),
+    #[non_exhaustive]
     UnknownDependency(String),
     // REFACTOR: move display logic into this type
     #[non_exhaustive]
     UnexpectedInternalDependencyVersion,
     #[non_exhaustive]
     InvalidUtf8(PathBuf),
+    /// An explicit version was requested without specifying the dependencies
+    /// to rewrite, which would rewrite every external dependency.
+    #[non_exhaustive]
+    ExplicitVersionWithoutDependencies,
 }
 
 impl From<FromFileError> for LintError {
@@ -72,6 +101,14 @@ impl From<EnumeratePackageManifestsError> for LintError {
     }
 }
 
+impl From<WriteError> for LintError {
+    fn from(err: WriteError) -> Self {
+        Self {
+            kind: LintErrorKind::Write(err),
+        }
+    }
+}
+
 impl From<LintErrorKind> for LintError {
     fn from(kind: LintErrorKind) -> Self {
         Self { kind }
@@ -87,30 +124,316 @@ fn most_common_dependency_version(
         .map(|(dependency_version, package_manifests)| {
             (dependency_version, package_manifests.len())
         })
-        // Take the max by value
-        .max_by(|a, b| a.1.cmp(&b.1))
+        // Take the max by value, breaking ties deterministically
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
         .map(|(k, _v)| k.to_owned())
 }
 
-pub fn lint_dependency_version<P, S>(root: P, dependencies: &[S]) -> Result<(), LintError>
+/// The highest version in use, comparing the version following any range
+/// operator. Versions which are not semantic versions rank lowest.
+fn highest_dependency_version<'a, I>(dependency_versions: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    dependency_versions
+        .into_iter()
+        .max_by_key(|dependency_version| {
+            let (_operator, version) = split_operator(dependency_version);
+            (Version::parse(version).ok(), *dependency_version)
+        })
+        .map(ToOwned::to_owned)
+}
+
+/// The narrowest range satisfying every declared version, or `None` when a
+/// declared version is not a range or the ranges share no version.
+fn narrowest_common_range<'a, I>(dependency_versions: I) -> Option<Range>
+where
+    I: IntoIterator<Item = &'a String>,
+{
+    dependency_versions
+        .into_iter()
+        .map(|dependency_version| Range::parse(dependency_version).ok())
+        .reduce(|accumulator, range| accumulator?.intersect(&range?))
+        .flatten()
+}
+
+/// Sort the packages using each version, for deterministic output.
+fn packages_by_version(
+    package_manifests_by_dependency_version: HashMap<String, Vec<String>>,
+) -> BTreeMap<String, Vec<String>> {
+    package_manifests_by_dependency_version
+        .into_iter()
+        .map(|(dependency_version, mut package_manifests)| {
+            package_manifests.sort_unstable();
+            (dependency_version, package_manifests)
+        })
+        .collect()
+}
+
+/// How declared versions of an external dependency are compared for
+/// consistency.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DependencyVersionMode {
+    /// Every package must declare the same version string.
+    #[default]
+    Exact,
+    /// The declared ranges must share at least one satisfying version, such
+    /// as `^1.2.0` and `^1.3.0`.
+    Semver,
+}
+
+/// Version to which `modify_dependency_version` rewrites every use of a
+/// dependency.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DependencyVersionStrategy {
+    /// The version used by the most packages.
+    MostCommon,
+    /// The highest version in use.
+    Highest,
+    /// The specified version.
+    Explicit(String),
+}
+
+/// Versions of an external dependency used across the monorepo.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct InconsistentDependencyVersion {
+    pub dependency: String,
+    /// The version used by the most packages.
+    pub expected: String,
+    /// Paths to the manifests of the packages using each version.
+    pub packages_by_version: BTreeMap<String, Vec<String>>,
+}
+
+/// Different versions of an external dependency which a single install
+/// satisfies, found in `semver` mode.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CompatibleDependencyVersion {
+    pub dependency: String,
+    /// The narrowest range satisfying every package.
+    pub suggested: String,
+    /// Paths to the manifests of the packages using each version.
+    pub packages_by_version: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyVersionReport {
+    inconsistencies: Vec<InconsistentDependencyVersion>,
+    compatible: Vec<CompatibleDependencyVersion>,
+    policy_violations: Vec<PolicyViolation>,
+}
+
+impl DependencyVersionReport {
+    pub fn is_empty(&self) -> bool {
+        self.inconsistencies.is_empty() && self.policy_violations.is_empty()
+    }
+
+    /// Dependencies used with more than one version.
+    pub fn iter(&self) -> impl Iterator<Item = &InconsistentDependencyVersion> {
+        self.inconsistencies.iter()
+    }
+
+    /// Dependencies used with different but compatible versions, which are
+    /// not lint issues.
+    pub fn compatible(&self) -> impl Iterator<Item = &CompatibleDependencyVersion> {
+        self.compatible.iter()
+    }
+
+    /// Dependency declarations violating the version policy.
+    pub fn policy_violations(&self) -> impl Iterator<Item = &PolicyViolation> {
+        self.policy_violations.iter()
+    }
+}
+
+/// Summary table of dependency, versions and the packages using each
+/// version, followed by the version policy violations.
+impl Display for DependencyVersionReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.policy_violations.is_empty() {
+            writeln!(f, "Dependency version policy violations:")?;
+            for violation in self.policy_violations.iter() {
+                writeln!(f, "\t{}", violation)?;
+            }
+            if !self.inconsistencies.is_empty() {
+                writeln!(f)?;
+            }
+        }
+        if self.inconsistencies.is_empty() {
+            return Ok(());
+        }
+
+        let rows: Vec<[&str; 3]> = self
+            .inconsistencies
+            .iter()
+            .flat_map(|inconsistency| {
+                inconsistency
+                    .packages_by_version
+                    .iter()
+                    .enumerate()
+                    .flat_map(move |(version_index, (version, packages))| {
+                        packages
+                            .iter()
+                            .enumerate()
+                            .map(move |(package_index, package)| {
+                                [
+                                    match (version_index, package_index) {
+                                        (0, 0) => inconsistency.dependency.as_str(),
+                                        _ => "",
+                                    },
+                                    match package_index {
+                                        0 => version.as_str(),
+                                        _ => "",
+                                    },
+                                    package.as_str(),
+                                ]
+                            })
+                    })
+            })
+            .collect();
+
+        let header = ["Dependency", "Version", "Packages"];
+        let width = |column: usize| {
+            rows.iter()
+                .chain(std::iter::once(&header))
+                .map(|row| row[column].len())
+                .max()
+                .unwrap_or_default()
+        };
+        let (dependency_width, version_width) = (width(0), width(1));
+        for row in std::iter::once(&header).chain(rows.iter()) {
+            writeln!(
+                f,
+                "{:dependency_width$}  {:version_width$}  {}",
+                row[0], row[1], row[2]
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a dependency name matches an ignore pattern, which is either a
+/// package name or a prefix ending in `*`, such as `@types/*`.
+fn is_ignored<S>(dependency: &str, ignore: &[S]) -> bool
+where
+    S: AsRef<str>,
+{
+    ignore
+        .iter()
+        .any(|pattern| match pattern.as_ref().strip_suffix('*') {
+            Some(prefix) => dependency.starts_with(prefix),
+            None => dependency == pattern.as_ref(),
+        })
+}
+
+/// Names of every dependency which does not resolve to an internal package.
+fn external_dependency_names(
+    package_manifest_by_package_name: &HashMap<String, PackageManifest>,
+) -> BTreeSet<String> {
+    package_manifest_by_package_name
+        .values()
+        .flat_map(PackageManifest::dependencies_iter)
+        .filter(|(dependency_name, dependency_version)| {
+            match dependency_version.as_str().map(DependencySpecifier::parse) {
+                Some(
+                    DependencySpecifier::Workspace(_)
+                    | DependencySpecifier::File(_)
+                    | DependencySpecifier::Link(_),
+                ) => false,
+                Some(specifier) => !package_manifest_by_package_name
+                    .contains_key(specifier.package_name(dependency_name)),
+                None => !package_manifest_by_package_name.contains_key(*dependency_name),
+            }
+        })
+        .map(|(dependency_name, _)| dependency_name.to_owned())
+        .collect()
+}
+
+/// Dependencies to check for consistency: the specified dependencies, or
+/// every external dependency not ignored by the monorepo configuration.
+/// Dependencies governed by the version policy are checked against the
+/// policy as well.
+fn dependencies_to_check<S>(
+    package_manifest_by_package_name: &HashMap<String, PackageManifest>,
+    configuration: &MonorepoConfiguration,
+    dependencies: &[S],
+) -> Vec<String>
+where
+    S: AsRef<str>,
+{
+    match dependencies.is_empty() {
+        true => external_dependency_names(package_manifest_by_package_name)
+            .into_iter()
+            .filter(|dependency| !is_ignored(dependency, &configuration.dependency_version.ignore))
+            .collect(),
+        false => dependencies
+            .iter()
+            .map(|dependency| dependency.as_ref().to_owned())
+            .collect(),
+    }
+}
+
+/// Whether the version policy applies to a dependency: every dependency
+/// when none are specified, otherwise only the specified dependencies.
+fn is_policy_in_scope<S>(dependencies: &[S], dependency: &str) -> bool
+where
+    S: AsRef<str>,
+{
+    dependencies.is_empty()
+        || dependencies
+            .iter()
+            .any(|specified| specified.as_ref() == dependency)
+}
+
+/// Enumerate external dependencies used with more than one version, and
+/// dependency declarations violating the version policy in `monorepo.json`.
+/// When no dependencies are specified, every external dependency not ignored
+/// by the monorepo configuration is checked. In `semver` mode, only versions
+/// sharing no satisfying version are inconsistent.
+pub fn lint_dependency_version_report<P, S>(
+    root: P,
+    dependencies: &[S],
+) -> Result<DependencyVersionReport, LintError>
 where
     P: AsRef<Path>,
-    S: AsRef<str> + std::fmt::Display,
+    S: AsRef<str>,
 {
     let root = root.as_ref();
 
     let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let configuration = MonorepoConfiguration::from_directory(root)?;
     let package_manifest_by_package_name = lerna_manifest.package_manifests_by_package_name()?;
 
-    let mut is_exit_success = true;
+    let policy_violations = policy_violations(
+        &package_manifest_by_package_name,
+        &configuration.dependency_version,
+        |dependency| is_policy_in_scope(dependencies, dependency),
+    );
 
-    for dependency in dependencies {
+    let mut inconsistencies = Vec::new();
+    let mut compatible = Vec::new();
+
+    for dependency in dependencies_to_check(
+        &package_manifest_by_package_name,
+        &configuration,
+        dependencies,
+    ) {
         let package_manifests_by_dependency_version: HashMap<String, Vec<String>> =
             package_manifest_by_package_name
                 .values()
+                // Exceptions to the version policy may diverge deliberately
+                .filter(|package_manifest| {
+                    !configuration
+                        .dependency_version
+                        .is_exception(&package_manifest.contents.name, &dependency)
+                })
                 .filter_map(|package_manifest| {
                     package_manifest
-                        .get_dependency_version(dependency)
+                        .get_dependency_version(&dependency)
                         .map(|dependency_version| (package_manifest, dependency_version))
                 })
                 .try_fold(
@@ -134,37 +457,193 @@ where
                 )?;
 
         if package_manifests_by_dependency_version.keys().len() <= 1 {
-            return Ok(());
+            continue;
+        }
+
+        if configuration.dependency_version.mode == DependencyVersionMode::Semver {
+            if let Some(range) =
+                narrowest_common_range(package_manifests_by_dependency_version.keys())
+            {
+                compatible.push(CompatibleDependencyVersion {
+                    dependency,
+                    suggested: range.to_shorthand(),
+                    packages_by_version: packages_by_version(
+                        package_manifests_by_dependency_version,
+                    ),
+                });
+                continue;
+            }
         }
 
         let expected_version_number =
             most_common_dependency_version(&package_manifests_by_dependency_version)
                 .ok_or_else(|| LintErrorKind::UnknownDependency(dependency.to_string()))?;
 
-        println!("Linting versions of dependency \"{}\"", &dependency);
+        inconsistencies.push(InconsistentDependencyVersion {
+            dependency,
+            expected: expected_version_number,
+            packages_by_version: packages_by_version(package_manifests_by_dependency_version),
+        });
+    }
 
-        package_manifests_by_dependency_version
-            .into_iter()
-            // filter out the packages using the expected dependency version
-            .filter(|(dependency_version, _package_manifests)| {
-                !dependency_version.eq(&expected_version_number)
-            })
-            .for_each(|(dependency_version, package_manifests)| {
-                package_manifests.into_iter().for_each(|package_manifest| {
-                    println!(
-                        "\tIn {}, expected version {} but found version {}",
-                        &package_manifest, &expected_version_number, dependency_version
-                    );
-                });
-            });
+    Ok(DependencyVersionReport {
+        inconsistencies,
+        compatible,
+        policy_violations,
+    })
+}
+
+pub fn lint_dependency_version<P, S>(root: P, dependencies: &[S]) -> Result<(), LintError>
+where
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let report = lint_dependency_version_report(root, dependencies)?;
 
-        is_exit_success = false;
+    for compatible in report.compatible() {
+        println!(
+            "Versions of dependency \"{}\" are compatible, the narrowest range satisfying every package is {}",
+            &compatible.dependency, &compatible.suggested
+        );
     }
 
-    if !is_exit_success {
+    if !report.is_empty() {
+        println!("\n{}", report);
         return Err(LintError {
             kind: LintErrorKind::UnexpectedInternalDependencyVersion,
         });
     }
     Ok(())
 }
+
+/// Whether a declared dependency is a version range, as opposed to an alias,
+/// a local path or a remote source, which do not install from the registry
+/// under the dependency's name.
+fn is_range(dependency_version: &str) -> bool {
+    matches!(
+        DependencySpecifier::parse(dependency_version),
+        DependencySpecifier::Range(_)
+    )
+}
+
+/// Apply the version policy in `monorepo.json`, then rewrite every use of
+/// each remaining dependency declared with a version range, in every
+/// dependency group, to the version chosen by `strategy`. When no dependencies are specified, every external
+/// dependency not ignored by the monorepo configuration is rewritten, except
+/// with an explicit version, which requires specified dependencies. In
+/// `semver` mode, dependencies whose versions are compatible are left as is.
+pub fn modify_dependency_version<P, S>(
+    root: P,
+    dependencies: &[S],
+    strategy: &DependencyVersionStrategy,
+) -> Result<(), LintError>
+where
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    let root = root.as_ref();
+
+    if matches!(strategy, DependencyVersionStrategy::Explicit(_)) && dependencies.is_empty() {
+        Err(LintErrorKind::ExplicitVersionWithoutDependencies)?;
+    }
+
+    let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let mut package_manifest_by_package_name =
+        lerna_manifest.package_manifests_by_package_name()?;
+
+    let configuration = MonorepoConfiguration::from_directory(root)?;
+
+    let mut dirty_package_names = apply_policy(
+        &mut package_manifest_by_package_name,
+        &configuration.dependency_version,
+        |dependency| is_policy_in_scope(dependencies, dependency),
+    );
+
+    for dependency in dependencies_to_check(
+        &package_manifest_by_package_name,
+        &configuration,
+        dependencies,
+    ) {
+        let dependency = dependency.as_str();
+        let mut package_names_by_dependency_version: HashMap<String, Vec<String>> = HashMap::new();
+        for package_manifest in package_manifest_by_package_name.values() {
+            if configuration
+                .dependency_version
+                .is_exception(&package_manifest.contents.name, dependency)
+            {
+                continue;
+            }
+            for dependency_group in DependencyGroup::VALUES {
+                if let Some(dependency_version) = package_manifest
+                    .contents
+                    .extra_fields
+                    .get(dependency_group)
+                    .and_then(|dependencies| dependencies.get(dependency))
+                    .and_then(serde_json::Value::as_str)
+                    .filter(|dependency_version| is_range(dependency_version))
+                {
+                    package_names_by_dependency_version
+                        .entry(dependency_version.to_owned())
+                        .or_default()
+                        .push(package_manifest.contents.name.to_owned());
+                }
+            }
+        }
+
+        if configuration.dependency_version.mode == DependencyVersionMode::Semver
+            && !matches!(strategy, DependencyVersionStrategy::Explicit(_))
+            && narrowest_common_range(package_names_by_dependency_version.keys()).is_some()
+        {
+            continue;
+        }
+
+        let expected_version_number = match strategy {
+            DependencyVersionStrategy::MostCommon => {
+                most_common_dependency_version(&package_names_by_dependency_version)
+            }
+            DependencyVersionStrategy::Highest => {
+                highest_dependency_version(package_names_by_dependency_version.keys())
+            }
+            DependencyVersionStrategy::Explicit(version) => Some(version.to_owned()),
+        };
+        // Nothing to rewrite when the dependency is unused
+        let expected_version_number = match expected_version_number {
+            Some(version) if !package_names_by_dependency_version.is_empty() => version,
+            _ => continue,
+        };
+
+        for (package_name, package_manifest) in package_manifest_by_package_name.iter_mut() {
+            if configuration
+                .dependency_version
+                .is_exception(package_name, dependency)
+            {
+                continue;
+            }
+            for dependency_group in DependencyGroup::VALUES {
+                let dependency_version = match package_manifest
+                    .contents
+                    .extra_fields
+                    .get_mut(dependency_group)
+                    .and_then(serde_json::Value::as_object_mut)
+                    .and_then(|dependencies| dependencies.get_mut(dependency))
+                {
+                    Some(dependency_version) => dependency_version,
+                    None => continue,
+                };
+                let is_rewritable = dependency_version.as_str().is_some_and(is_range);
+                if is_rewritable && dependency_version != &expected_version_number {
+                    *dependency_version = expected_version_number.clone().into();
+                    dirty_package_names.insert(package_name.to_owned());
+                }
+            }
+        }
+    }
+
+    for (package_name, package_manifest) in package_manifest_by_package_name {
+        if dirty_package_names.contains(&package_name) {
+            PackageManifest::write(root, package_manifest)?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::FromFileError;
+use crate::monorepo_configuration::{BoundariesConfiguration, MonorepoConfiguration};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct BoundariesLintError {
+    pub kind: BoundariesLintErrorKind,
+}
+
+impl Display for BoundariesLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            BoundariesLintErrorKind::BoundaryViolations(report) => {
+                writeln!(
+                    f,
+                    "found internal dependencies crossing package boundaries\n"
+                )?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting package boundaries"),
+        }
+    }
+}
+
+impl std::error::Error for BoundariesLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            BoundariesLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            BoundariesLintErrorKind::FromFile(err) => Some(err),
+            BoundariesLintErrorKind::BoundaryViolations(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for BoundariesLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: BoundariesLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for BoundariesLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: BoundariesLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<BoundariesLintReport> for BoundariesLintError {
+    fn from(report: BoundariesLintReport) -> Self {
+        Self {
+            kind: BoundariesLintErrorKind::BoundaryViolations(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum BoundariesLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    BoundaryViolations(BoundariesLintReport),
+}
+
+/// An internal dependency forbidden by a boundary rule.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BoundaryViolation {
+    pub package: String,
+    /// Path to the manifest of the depending package, relative to the
+    /// monorepo root.
+    pub path: PathBuf,
+    pub dependency: String,
+    pub dependency_tags: Vec<String>,
+    /// The violated rule, such as `layer:ui may not depend on layer:server`.
+    pub rule: String,
+}
+
+impl Display for BoundaryViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {} [{}] violates rule: {}",
+            self.package,
+            self.dependency,
+            self.dependency_tags.join(", "),
+            self.rule
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct BoundariesLintReport(Vec<BoundaryViolation>);
+
+impl BoundariesLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BoundaryViolation> {
+        self.0.iter()
+    }
+}
+
+impl Display for BoundariesLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for violation in self.0.iter() {
+            writeln!(f, "\t{}", violation)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tags of a package, from the `monorepo` field of its manifest and from the
+/// monorepo configuration.
+fn package_tags(
+    package_manifest: &PackageManifest,
+    configuration: &BoundariesConfiguration,
+) -> Vec<String> {
+    let manifest_tags = package_manifest
+        .contents
+        .extra_fields
+        .get("monorepo")
+        .and_then(|monorepo| monorepo.get("tags"))
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(serde_json::Value::as_str)
+        .map(ToOwned::to_owned);
+    let configured_tags = configuration
+        .tags
+        .get(&package_manifest.contents.name)
+        .into_iter()
+        .flatten()
+        .cloned();
+    let mut tags: Vec<String> = manifest_tags.chain(configured_tags).collect();
+    tags.sort_unstable();
+    tags.dedup();
+    tags
+}
+
+/// Check every internal dependency against the boundary rules in
+/// `monorepo.json`.
+pub fn lint_report<P>(root: P) -> Result<BoundariesLintReport, BoundariesLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<BoundariesLintReport, BoundariesLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let configuration = MonorepoConfiguration::from_directory(root)?.boundaries;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut violations = Vec::new();
+        for package_manifest in package_manifests_by_package_name.values() {
+            let tags = package_tags(package_manifest, &configuration);
+            let rules: Vec<_> = configuration
+                .rules
+                .iter()
+                .filter(|rule| tags.contains(&rule.from))
+                .collect();
+            if rules.is_empty() {
+                continue;
+            }
+            for dependency in
+                package_manifest.internal_dependencies_iter(&package_manifests_by_package_name)
+            {
+                let dependency_tags = package_tags(dependency, &configuration);
+                for rule in rules.iter().filter(|rule| !rule.allows(&dependency_tags)) {
+                    violations.push(BoundaryViolation {
+                        package: package_manifest.contents.name.to_owned(),
+                        path: package_manifest.path(),
+                        dependency: dependency.contents.name.to_owned(),
+                        dependency_tags: dependency_tags.clone(),
+                        rule: rule.to_string(),
+                    });
+                }
+            }
+        }
+        // Sort for deterministic output
+        violations.sort_unstable_by(|a, b| {
+            (&a.path, &a.dependency, &a.rule).cmp(&(&b.path, &b.dependency, &b.rule))
+        });
+        violations.dedup();
+
+        Ok(BoundariesLintReport(violations))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), BoundariesLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
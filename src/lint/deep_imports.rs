@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::import_specifiers::{package_imports, package_name, ScanImportsError};
+use crate::io::FromFileError;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_exports::PackageExports;
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct DeepImportsLintError {
+    pub kind: DeepImportsLintErrorKind,
+}
+
+impl Display for DeepImportsLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DeepImportsLintErrorKind::DeepImports(report) => {
+                writeln!(f, "found imports of unexported internal modules\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting deep imports"),
+        }
+    }
+}
+
+impl std::error::Error for DeepImportsLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            DeepImportsLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            DeepImportsLintErrorKind::FromFile(err) => Some(err),
+            DeepImportsLintErrorKind::ScanImports(err) => Some(err),
+            DeepImportsLintErrorKind::DeepImports(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for DeepImportsLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: DeepImportsLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for DeepImportsLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: DeepImportsLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<ScanImportsError> for DeepImportsLintError {
+    fn from(err: ScanImportsError) -> Self {
+        Self {
+            kind: DeepImportsLintErrorKind::ScanImports(err),
+        }
+    }
+}
+
+impl From<DeepImportsLintReport> for DeepImportsLintError {
+    fn from(report: DeepImportsLintReport) -> Self {
+        Self {
+            kind: DeepImportsLintErrorKind::DeepImports(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum DeepImportsLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    ScanImports(ScanImportsError),
+    #[non_exhaustive]
+    DeepImports(DeepImportsLintReport),
+}
+
+/// An import of a module which an internal package does not expose.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct DeepImport {
+    /// The imported internal package.
+    pub dependency: String,
+    pub specifier: String,
+    /// Path to the source file, relative to the monorepo root.
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+impl Display for DeepImport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: imports {} which is not exported by {}",
+            self.file.display(),
+            self.line,
+            self.specifier,
+            self.dependency
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageDeepImports {
+    pub name: String,
+    pub path: PathBuf,
+    pub imports: Vec<DeepImport>,
+}
+
+impl Display for PackageDeepImports {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Package imports unexported internal modules: {:?}",
+            self.path
+        )?;
+        for import in self.imports.iter() {
+            writeln!(f, "\t{}", import)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct DeepImportsLintReport(Vec<PackageDeepImports>);
+
+impl DeepImportsLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageDeepImports> {
+        self.0.iter()
+    }
+}
+
+impl Display for DeepImportsLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether a subpath of a package, such as `.` or `./feature`, is part of
+/// its public interface: exported by its `exports` field, or, without one,
+/// its `main` entry point.
+fn is_public_subpath(package_manifest: &PackageManifest, subpath: &str) -> bool {
+    let extra_fields = &package_manifest.contents.extra_fields;
+    if let Some(exports) = extra_fields.get("exports") {
+        return match PackageExports::parse(exports) {
+            Ok(exports) => exports.is_exported(subpath),
+            // Reported by `lint exports`
+            Err(_) => true,
+        };
+    }
+    if subpath == "." {
+        return true;
+    }
+    let main = match extra_fields.get("main").and_then(serde_json::Value::as_str) {
+        Some(main) => main,
+        None => return false,
+    };
+    let main = format!("./{}", main.trim_start_matches("./"));
+    let main_without_extension = match main.rsplit_once('.') {
+        Some((stem, extension)) if !extension.contains('/') => stem,
+        _ => main.as_str(),
+    };
+    subpath == main || subpath == main_without_extension
+}
+
+fn deep_imports(
+    root: &Path,
+    package_manifest: &PackageManifest,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Result<Vec<DeepImport>, DeepImportsLintError> {
+    let mut deep_imports = Vec::new();
+    for (file, import) in package_imports(root, package_manifest)? {
+        let dependency = match package_name(&import.specifier) {
+            Some(dependency) if dependency != package_manifest.contents.name => dependency,
+            _ => continue,
+        };
+        let dependency_manifest = match package_manifests_by_package_name.get(dependency) {
+            Some(dependency_manifest) => dependency_manifest,
+            None => continue,
+        };
+        let subpath = match &import.specifier[dependency.len()..] {
+            "" => String::from("."),
+            rest => format!(".{}", rest),
+        };
+        if !is_public_subpath(dependency_manifest, &subpath) {
+            deep_imports.push(DeepImport {
+                dependency: dependency.to_owned(),
+                specifier: import.specifier,
+                file,
+                line: import.line,
+            });
+        }
+    }
+    Ok(deep_imports)
+}
+
+/// Find imports between internal packages of modules which the imported
+/// package does not expose through its `exports` field, or through its
+/// `main` entry point when it has no `exports` field.
+pub fn lint_report<P>(root: P) -> Result<DeepImportsLintReport, DeepImportsLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<DeepImportsLintReport, DeepImportsLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut packages = Vec::new();
+        for package_manifest in package_manifests_by_package_name.values() {
+            let imports = deep_imports(root, package_manifest, &package_manifests_by_package_name)?;
+            if !imports.is_empty() {
+                packages.push(PackageDeepImports {
+                    name: package_manifest.contents.name.to_owned(),
+                    path: package_manifest.path(),
+                    imports,
+                });
+            }
+        }
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(DeepImportsLintReport(packages))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), DeepImportsLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
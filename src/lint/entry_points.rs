@@ -0,0 +1,375 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::{normalize_path, FromFileError};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_exports::PackageExports;
+use crate::package_manifest::PackageManifest;
+use crate::typescript_config::{ResolvedTypescriptConfig, TypescriptConfig};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct EntryPointLintError {
+    pub kind: EntryPointLintErrorKind,
+}
+
+impl Display for EntryPointLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            EntryPointLintErrorKind::UnemittedEntryPoints(report) => {
+                writeln!(f, "found entry points which tsc will not emit\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting package entry points"),
+        }
+    }
+}
+
+impl std::error::Error for EntryPointLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            EntryPointLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            EntryPointLintErrorKind::FromFile(err) => Some(err),
+            EntryPointLintErrorKind::UnemittedEntryPoints(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for EntryPointLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: EntryPointLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for EntryPointLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: EntryPointLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EntryPointLintReport> for EntryPointLintError {
+    fn from(report: EntryPointLintReport) -> Self {
+        Self {
+            kind: EntryPointLintErrorKind::UnemittedEntryPoints(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EntryPointLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    UnemittedEntryPoints(EntryPointLintReport),
+}
+
+/// An entry point declared in a package manifest which the TypeScript
+/// compiler will not produce. `field` names the manifest field declaring
+/// the entry point and `entry` is its path relative to the monorepo root.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EntryPointIssue {
+    /// The entry point is not inside the directory tsc emits this kind of file into.
+    #[non_exhaustive]
+    OutsideOutputDirectory {
+        field: String,
+        entry: PathBuf,
+        output_directory: PathBuf,
+    },
+    /// The entry point is a declaration file but `declaration` is not enabled.
+    #[non_exhaustive]
+    DeclarationsNotEmitted { field: String, entry: PathBuf },
+    /// The entry point is a JavaScript file but `emitDeclarationOnly` is enabled.
+    #[non_exhaustive]
+    JavascriptNotEmitted { field: String, entry: PathBuf },
+    /// No source file under `rootDir` compiles to the entry point.
+    #[non_exhaustive]
+    MissingSource {
+        field: String,
+        entry: PathBuf,
+        root_dir: PathBuf,
+    },
+}
+
+impl Display for EntryPointIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryPointIssue::OutsideOutputDirectory {
+                field,
+                entry,
+                output_directory,
+            } => write!(
+                f,
+                "{}: {:?} is outside of output directory {:?}",
+                field, entry, output_directory
+            ),
+            EntryPointIssue::DeclarationsNotEmitted { field, entry } => write!(
+                f,
+                "{}: {:?} is a declaration file but `declaration` is not enabled",
+                field, entry
+            ),
+            EntryPointIssue::JavascriptNotEmitted { field, entry } => write!(
+                f,
+                "{}: {:?} is a JavaScript file but `emitDeclarationOnly` is enabled",
+                field, entry
+            ),
+            EntryPointIssue::MissingSource {
+                field,
+                entry,
+                root_dir,
+            } => write!(
+                f,
+                "{}: {:?} has no corresponding source file in {:?}",
+                field, entry, root_dir
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PackageEntryPointIssues {
+    pub path: PathBuf,
+    pub issues: Vec<EntryPointIssue>,
+}
+
+impl Display for PackageEntryPointIssues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "File contains entry points which tsc will not emit: {:?}",
+            self.path
+        )?;
+        for issue in self.issues.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EntryPointLintReport(Vec<PackageEntryPointIssues>);
+
+impl EntryPointLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageEntryPointIssues> {
+        self.0.iter()
+    }
+}
+
+impl Display for EntryPointLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+/// Entry points declared by `main`, `types`, `typings` and every target of
+/// a valid `exports` field, labelled by the field leading to them. Invalid
+/// `exports` fields are reported by the exports lint instead.
+fn entry_points(package_manifest: &PackageManifest) -> Vec<(String, String)> {
+    let extra_fields = &package_manifest.contents.extra_fields;
+    let mut entry_points: Vec<(String, String)> = ["main", "types", "typings"]
+        .iter()
+        .filter_map(|field| {
+            extra_fields
+                .get(*field)
+                .and_then(serde_json::Value::as_str)
+                .map(|entry| (field.to_string(), entry.to_owned()))
+        })
+        .collect();
+    if let Some(Ok(exports)) = extra_fields.get("exports").map(PackageExports::parse) {
+        entry_points.extend(
+            exports
+                .target_paths()
+                .into_iter()
+                .map(|(location, target)| (location, target.to_owned())),
+        );
+    }
+    entry_points
+}
+
+fn is_declaration_file(entry: &str) -> bool {
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|extension| entry.ends_with(extension))
+}
+
+/// Source file extensions which compile to an entry point with this extension.
+fn source_extensions(entry: &str, allow_js: bool) -> Option<&'static [&'static str]> {
+    let extensions: &'static [&'static str] = if entry.ends_with(".d.ts") {
+        &[".ts", ".tsx"]
+    } else if entry.ends_with(".d.mts") {
+        &[".mts"]
+    } else if entry.ends_with(".d.cts") {
+        &[".cts"]
+    } else if entry.ends_with(".js") {
+        match allow_js {
+            true => &[".ts", ".tsx", ".js", ".jsx"],
+            false => &[".ts", ".tsx"],
+        }
+    } else if entry.ends_with(".mjs") {
+        match allow_js {
+            true => &[".mts", ".mjs"],
+            false => &[".mts"],
+        }
+    } else if entry.ends_with(".cjs") {
+        match allow_js {
+            true => &[".cts", ".cjs"],
+            false => &[".cts"],
+        }
+    } else {
+        return None;
+    };
+    Some(extensions)
+}
+
+fn strip_output_extension(entry: &str) -> &str {
+    [".d.ts", ".d.mts", ".d.cts", ".js", ".mjs", ".cjs"]
+        .iter()
+        .find_map(|extension| entry.strip_suffix(extension))
+        .unwrap_or(entry)
+}
+
+fn lint_entry_point(
+    root: &Path,
+    package_manifest: &PackageManifest,
+    config: &ResolvedTypescriptConfig,
+    field: String,
+    entry: &str,
+) -> Option<EntryPointIssue> {
+    // Subpath patterns are checked through their static prefix
+    let pattern_prefix = entry.split('*').next().unwrap_or(entry);
+    let is_pattern = pattern_prefix.len() != entry.len();
+    let allow_js = config.is_enabled("allowJs");
+    let extensions = source_extensions(entry, allow_js)?;
+    let entry_path = normalize_path(&package_manifest.directory().join(pattern_prefix));
+
+    let is_declaration = is_declaration_file(entry);
+    if is_declaration && !config.is_enabled("declaration") && !config.is_enabled("composite") {
+        return Some(EntryPointIssue::DeclarationsNotEmitted {
+            field,
+            entry: entry_path,
+        });
+    }
+    if !is_declaration && config.is_enabled("emitDeclarationOnly") {
+        return Some(EntryPointIssue::JavascriptNotEmitted {
+            field,
+            entry: entry_path,
+        });
+    }
+
+    let output_directory = match is_declaration {
+        true => config.declaration_dir.as_ref().or(config.out_dir.as_ref()),
+        false => config.out_dir.as_ref(),
+    };
+    let output_directory = output_directory?;
+    let relative_entry = match entry_path.strip_prefix(output_directory) {
+        Ok(relative_entry) => relative_entry,
+        Err(_) => {
+            return Some(EntryPointIssue::OutsideOutputDirectory {
+                field,
+                entry: entry_path,
+                output_directory: output_directory.to_owned(),
+            })
+        }
+    };
+
+    // Without `rootDir` the compiler infers the source directory, so there
+    // is no way to map the entry point back to its source file
+    let root_dir = config.root_dir.as_ref()?;
+    if is_pattern {
+        return None;
+    }
+    let source_stem = root_dir.join(strip_output_extension(
+        relative_entry.to_str().unwrap_or_default(),
+    ));
+    let has_source = extensions.iter().any(|extension| {
+        let mut source = source_stem.clone().into_os_string();
+        source.push(extension);
+        root.join(source).is_file()
+    });
+    match has_source {
+        true => None,
+        false => Some(EntryPointIssue::MissingSource {
+            field,
+            entry: entry_path,
+            root_dir: root_dir.to_owned(),
+        }),
+    }
+}
+
+/// Enumerate the entry points of every package which will not be produced by
+/// compiling the package's TypeScript project.
+pub fn lint_report<P>(root: P) -> Result<EntryPointLintReport, EntryPointLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<EntryPointLintReport, EntryPointLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut packages = package_manifests_by_package_name
+            .values()
+            // Packages are not required to contain TypeScript
+            .filter(|package_manifest| {
+                root.join(package_manifest.directory())
+                    .join(TypescriptConfig::FILENAME)
+                    .is_file()
+            })
+            .map(
+                |package_manifest| -> Result<PackageEntryPointIssues, FromFileError> {
+                    let config =
+                        TypescriptConfig::from_directory(root, &package_manifest.directory())?
+                            .resolve(root)?;
+                    let issues = match config.is_enabled("noEmit") {
+                        true => Vec::new(),
+                        false => entry_points(package_manifest)
+                            .into_iter()
+                            .filter_map(|(field, entry)| {
+                                lint_entry_point(root, package_manifest, &config, field, &entry)
+                            })
+                            .collect(),
+                    };
+                    Ok(PackageEntryPointIssues {
+                        path: package_manifest.path(),
+                        issues,
+                    })
+                },
+            )
+            .filter(|maybe_package| {
+                maybe_package
+                    .as_ref()
+                    .map(|package| !package.issues.is_empty())
+                    .unwrap_or(true)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(EntryPointLintReport(packages))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), EntryPointLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
@@ -0,0 +1,192 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::FromFileError;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_exports::{ExportsIssue, PackageExports};
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ExportsLintError {
+    pub kind: ExportsLintErrorKind,
+}
+
+impl Display for ExportsLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ExportsLintErrorKind::InvalidExports(report) => {
+                writeln!(f, "found invalid package exports\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting package exports"),
+        }
+    }
+}
+
+impl std::error::Error for ExportsLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ExportsLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            ExportsLintErrorKind::FromFile(err) => Some(err),
+            ExportsLintErrorKind::InvalidExports(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for ExportsLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: ExportsLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for ExportsLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ExportsLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<ExportsLintReport> for ExportsLintError {
+    fn from(report: ExportsLintReport) -> Self {
+        Self {
+            kind: ExportsLintErrorKind::InvalidExports(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ExportsLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    InvalidExports(ExportsLintReport),
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageExportsIssues {
+    pub name: String,
+    pub path: PathBuf,
+    pub issues: Vec<ExportsIssue>,
+}
+
+impl Display for PackageExportsIssues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "File contains invalid exports: {:?}", self.path)?;
+        for issue in self.issues.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct ExportsLintReport(Vec<PackageExportsIssues>);
+
+impl ExportsLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageExportsIssues> {
+        self.0.iter()
+    }
+}
+
+impl Display for ExportsLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+/// Find targets which do not exist on disk. For subpath patterns only the
+/// directory preceding the wildcard is required to exist.
+fn missing_targets(
+    root: &Path,
+    package_manifest: &PackageManifest,
+    exports: &PackageExports,
+) -> Vec<ExportsIssue> {
+    let package_directory = root.join(package_manifest.directory());
+    exports
+        .target_paths()
+        .into_iter()
+        .filter(|(_location, target)| match target.split_once('*') {
+            Some((prefix, _suffix)) => {
+                let prefix_directory = match prefix.rsplit_once('/') {
+                    Some((directory, _)) => directory,
+                    None => prefix,
+                };
+                !package_directory.join(prefix_directory).is_dir()
+            }
+            None => !package_directory.join(target).is_file(),
+        })
+        .map(|(location, target)| ExportsIssue::MissingTarget {
+            location,
+            target: target.to_owned(),
+        })
+        .collect()
+}
+
+/// Validate the `exports` field of every internal package. When
+/// `check_files` is set, also require every target to exist on disk, which
+/// is only meaningful after the packages are built.
+pub fn lint_report<P>(root: P, check_files: bool) -> Result<ExportsLintReport, ExportsLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path, check_files: bool) -> Result<ExportsLintReport, ExportsLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut packages: Vec<PackageExportsIssues> = package_manifests_by_package_name
+            .values()
+            .filter_map(|package_manifest| {
+                let exports = package_manifest.contents.extra_fields.get("exports")?;
+                let issues = match PackageExports::parse(exports) {
+                    Ok(exports) if check_files => missing_targets(root, package_manifest, &exports),
+                    Ok(_) => Vec::new(),
+                    Err(err) => err.0,
+                };
+                match issues.is_empty() {
+                    true => None,
+                    false => Some(PackageExportsIssues {
+                        name: package_manifest.contents.name.to_owned(),
+                        path: package_manifest.path(),
+                        issues,
+                    }),
+                }
+            })
+            .collect();
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(ExportsLintReport(packages))
+    }
+    inner(root.as_ref(), check_files)
+}
+
+pub fn lint<P>(root: P, check_files: bool) -> Result<(), ExportsLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root, check_files)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::annotation::json_property_line;
+use crate::configuration_file::ConfigurationFile;
+use crate::import_specifiers::{package_imports, package_name, ScanImportsError};
+use crate::io::{read_to_string, FromFileError};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ImportsLintError {
+    pub kind: ImportsLintErrorKind,
+}
+
+impl Display for ImportsLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ImportsLintErrorKind::InvalidDependencies(report) => {
+                writeln!(f, "found undeclared or unused dependencies\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting imports"),
+        }
+    }
+}
+
+impl std::error::Error for ImportsLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ImportsLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            ImportsLintErrorKind::FromFile(err) => Some(err),
+            ImportsLintErrorKind::ScanImports(err) => Some(err),
+            ImportsLintErrorKind::InvalidDependencies(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for ImportsLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: ImportsLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for ImportsLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ImportsLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<ScanImportsError> for ImportsLintError {
+    fn from(err: ScanImportsError) -> Self {
+        Self {
+            kind: ImportsLintErrorKind::ScanImports(err),
+        }
+    }
+}
+
+impl From<ImportsLintReport> for ImportsLintError {
+    fn from(report: ImportsLintReport) -> Self {
+        Self {
+            kind: ImportsLintErrorKind::InvalidDependencies(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImportsLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    ScanImports(ScanImportsError),
+    #[non_exhaustive]
+    InvalidDependencies(ImportsLintReport),
+}
+
+/// A disagreement between the imports of a package and its declared
+/// dependencies.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ImportIssue {
+    /// A source file imports a package which is not declared as a
+    /// dependency.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Undeclared {
+        dependency: String,
+        /// Whether the imported package is an internal package, which `link`
+        /// also needs declared to create a project reference.
+        internal: bool,
+        /// Path to the source file, relative to the monorepo root.
+        file: PathBuf,
+        line: usize,
+    },
+    /// A production dependency is never imported.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Unused {
+        dependency: String,
+        group: String,
+        /// Path to the package manifest, relative to the monorepo root.
+        file: PathBuf,
+        line: Option<usize>,
+    },
+}
+
+impl Display for ImportIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportIssue::Undeclared {
+                dependency,
+                internal,
+                file,
+                line,
+            } => write!(
+                f,
+                "{}:{}: imports {}package {} which is not declared as a dependency",
+                file.display(),
+                line,
+                if *internal { "internal " } else { "" },
+                dependency
+            ),
+            ImportIssue::Unused {
+                dependency,
+                group,
+                file,
+                line,
+            } => {
+                write!(f, "{}", file.display())?;
+                if let Some(line) = line {
+                    write!(f, ":{}", line)?;
+                }
+                write!(f, ": {} in {} is never imported", dependency, group)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageImportIssues {
+    pub name: String,
+    pub path: PathBuf,
+    pub issues: Vec<ImportIssue>,
+}
+
+impl Display for PackageImportIssues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Package imports do not match its dependencies: {:?}",
+            self.path
+        )?;
+        for issue in self.issues.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct ImportsLintReport(Vec<PackageImportIssues>);
+
+impl ImportsLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageImportIssues> {
+        self.0.iter()
+    }
+}
+
+impl Display for ImportsLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+fn import_issues(
+    root: &Path,
+    package_manifest: &PackageManifest,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Result<Vec<ImportIssue>, ImportsLintError> {
+    let declared: HashSet<&str> = package_manifest
+        .dependencies_iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    let package_name_of_self = package_manifest.contents.name.as_str();
+
+    let imports = package_imports(root, package_manifest)?;
+    // Packages without imports, such as packages without TypeScript
+    // sources, give no evidence of unused dependencies
+    let check_unused = !imports.is_empty();
+
+    let mut issues = Vec::new();
+    let mut imported = HashSet::new();
+    for (file, import) in imports {
+        let dependency = match package_name(&import.specifier) {
+            Some(dependency) if dependency != package_name_of_self => dependency.to_owned(),
+            _ => continue,
+        };
+        if !declared.contains(dependency.as_str()) {
+            issues.push(ImportIssue::Undeclared {
+                internal: package_manifests_by_package_name.contains_key(&dependency),
+                dependency: dependency.clone(),
+                file,
+                line: import.line,
+            });
+        }
+        imported.insert(dependency);
+    }
+
+    // Only production dependencies are expected to be imported: development
+    // dependencies are often tools, and type declarations are ambient
+    let dependency_group = "dependencies";
+    let mut unused: Vec<&String> = package_manifest
+        .contents
+        .extra_fields
+        .get(dependency_group)
+        .and_then(serde_json::Value::as_object)
+        .into_iter()
+        .flat_map(|dependencies| dependencies.keys())
+        .filter(|dependency| !dependency.starts_with("@types/") && !imported.contains(*dependency))
+        .collect();
+    if check_unused && !unused.is_empty() {
+        unused.sort_unstable();
+        let manifest_contents = read_to_string(&root.join(package_manifest.path()))?;
+        issues.extend(unused.into_iter().map(|dependency| ImportIssue::Unused {
+            dependency: dependency.to_owned(),
+            group: dependency_group.to_owned(),
+            file: package_manifest.path(),
+            line: json_property_line(&manifest_contents, &[dependency_group, dependency]),
+        }));
+    }
+
+    Ok(issues)
+}
+
+/// Compare the packages imported by the TypeScript sources of every internal
+/// package with its declared dependencies.
+pub fn lint_report<P>(root: P) -> Result<ImportsLintReport, ImportsLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<ImportsLintReport, ImportsLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut packages = Vec::new();
+        for package_manifest in package_manifests_by_package_name.values() {
+            let issues = import_issues(root, package_manifest, &package_manifests_by_package_name)?;
+            if !issues.is_empty() {
+                packages.push(PackageImportIssues {
+                    name: package_manifest.contents.name.to_owned(),
+                    path: package_manifest.path(),
+                    issues,
+                });
+            }
+        }
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(ImportsLintReport(packages))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), ImportsLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
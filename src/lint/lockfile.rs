@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::FromFileError;
+use crate::lockfile::{Lockfile, LockfileError, Resolution};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::{DependencySpecifier, PackageManifest};
+use crate::semver::{Range, Version};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LockfileLintError {
+    pub kind: LockfileLintErrorKind,
+}
+
+impl Display for LockfileLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            LockfileLintErrorKind::OutOfDateLockfile(report) => {
+                writeln!(f, "found dependencies the lockfile does not satisfy\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting lockfile"),
+        }
+    }
+}
+
+impl std::error::Error for LockfileLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            LockfileLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            LockfileLintErrorKind::FromFile(err) => Some(err),
+            LockfileLintErrorKind::Lockfile(err) => Some(err),
+            LockfileLintErrorKind::OutOfDateLockfile(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for LockfileLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: LockfileLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for LockfileLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: LockfileLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<LockfileError> for LockfileLintError {
+    fn from(err: LockfileError) -> Self {
+        Self {
+            kind: LockfileLintErrorKind::Lockfile(err),
+        }
+    }
+}
+
+impl From<LockfileLintReport> for LockfileLintError {
+    fn from(report: LockfileLintReport) -> Self {
+        Self {
+            kind: LockfileLintErrorKind::OutOfDateLockfile(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LockfileLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    Lockfile(LockfileError),
+    #[non_exhaustive]
+    OutOfDateLockfile(LockfileLintReport),
+}
+
+/// A declared dependency which the lockfile does not install as declared.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum LockfileIssue {
+    /// The lockfile does not contain the dependency.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Missing {
+        dependency: String,
+        specifier: String,
+    },
+    /// The locked version is outside the declared range.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Unsatisfied {
+        dependency: String,
+        specifier: String,
+        locked: String,
+    },
+    /// An internal package is installed from the registry instead of linked.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    NotLinked {
+        dependency: String,
+        specifier: String,
+        locked: String,
+    },
+}
+
+impl Display for LockfileIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockfileIssue::Missing {
+                dependency,
+                specifier,
+            } => write!(
+                f,
+                "{}@{} is missing from the lockfile",
+                dependency, specifier
+            ),
+            LockfileIssue::Unsatisfied {
+                dependency,
+                specifier,
+                locked,
+            } => write!(
+                f,
+                "{}@{} is locked to version {} which does not satisfy it",
+                dependency, specifier, locked
+            ),
+            LockfileIssue::NotLinked {
+                dependency,
+                specifier,
+                locked,
+            } => write!(
+                f,
+                "{}@{} is an internal package but is locked to version {} from the registry",
+                dependency, specifier, locked
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageLockfileIssues {
+    pub name: String,
+    pub path: PathBuf,
+    pub issues: Vec<LockfileIssue>,
+}
+
+impl Display for PackageLockfileIssues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "File contains dependencies the lockfile does not satisfy: {:?}",
+            self.path
+        )?;
+        for issue in self.issues.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct LockfileLintReport(Vec<PackageLockfileIssues>);
+
+impl LockfileLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageLockfileIssues> {
+        self.0.iter()
+    }
+}
+
+impl Display for LockfileLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+/// Dependency groups a package manager installs for a workspace package.
+const INSTALLED_DEPENDENCY_GROUPS: [&str; 3] =
+    ["dependencies", "devDependencies", "optionalDependencies"];
+
+fn lockfile_issues(
+    lockfile: &Lockfile,
+    package_manifest: &PackageManifest,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Vec<LockfileIssue> {
+    let mut issues = Vec::new();
+    for dependency_group in INSTALLED_DEPENDENCY_GROUPS {
+        let dependencies = package_manifest
+            .contents
+            .extra_fields
+            .get(dependency_group)
+            .and_then(serde_json::Value::as_object);
+        for (dependency, specifier) in dependencies.into_iter().flatten() {
+            let specifier = match specifier.as_str() {
+                Some(specifier) => specifier,
+                None => continue,
+            };
+            let parsed_specifier = DependencySpecifier::parse(specifier);
+            let internal = !parsed_specifier.is_remote()
+                && package_manifests_by_package_name
+                    .contains_key(parsed_specifier.package_name(dependency));
+
+            let resolution = lockfile.resolve(&package_manifest.directory(), dependency, specifier);
+            let locked = match resolution {
+                Some(Resolution::Link) => continue,
+                Some(Resolution::Version(locked)) => locked,
+                None if internal && !lockfile.records_workspace_links() => continue,
+                // Optional dependencies may fail to install on this platform
+                None if dependency_group == "optionalDependencies" => continue,
+                None => {
+                    issues.push(LockfileIssue::Missing {
+                        dependency: dependency.to_owned(),
+                        specifier: specifier.to_owned(),
+                    });
+                    continue;
+                }
+            };
+
+            if internal {
+                issues.push(LockfileIssue::NotLinked {
+                    dependency: dependency.to_owned(),
+                    specifier: specifier.to_owned(),
+                    locked,
+                });
+                continue;
+            }
+            let range = match &parsed_specifier {
+                DependencySpecifier::Range(range) => range,
+                DependencySpecifier::Alias { specifier, .. } => specifier,
+                _ => continue,
+            };
+            let satisfied = match (Range::parse(range), Version::parse(&locked)) {
+                (Ok(range), Ok(version)) => range.satisfies(&version),
+                // Versions such as git commits cannot be compared
+                _ => true,
+            };
+            if !satisfied {
+                issues.push(LockfileIssue::Unsatisfied {
+                    dependency: dependency.to_owned(),
+                    specifier: specifier.to_owned(),
+                    locked,
+                });
+            }
+        }
+    }
+    issues.sort_unstable_by_key(|issue| issue.to_string());
+    issues
+}
+
+/// Check that the lockfile at the monorepo root installs every dependency
+/// declared by every internal package, and links internal packages instead
+/// of fetching them from the registry.
+pub fn lint_report<P>(root: P) -> Result<LockfileLintReport, LockfileLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<LockfileLintReport, LockfileLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        let lockfile = Lockfile::from_directory(root)?;
+
+        let mut packages: Vec<PackageLockfileIssues> = package_manifests_by_package_name
+            .values()
+            .filter_map(|package_manifest| {
+                let issues = lockfile_issues(
+                    &lockfile,
+                    package_manifest,
+                    &package_manifests_by_package_name,
+                );
+                match issues.is_empty() {
+                    true => None,
+                    false => Some(PackageLockfileIssues {
+                        name: package_manifest.contents.name.to_owned(),
+                        path: package_manifest.path(),
+                        issues,
+                    }),
+                }
+            })
+            .collect();
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(LockfileLintReport(packages))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), LockfileLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::FromFileError;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::{DependencySpecifier, PackageManifest};
+use crate::semver::Range;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct PeerDependenciesLintError {
+    pub kind: PeerDependenciesLintErrorKind,
+}
+
+impl Display for PeerDependenciesLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            PeerDependenciesLintErrorKind::UnsatisfiedPeerDependencies(report) => {
+                writeln!(f, "found unsatisfied peer dependencies\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting peer dependencies"),
+        }
+    }
+}
+
+impl std::error::Error for PeerDependenciesLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            PeerDependenciesLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            PeerDependenciesLintErrorKind::FromFile(err) => Some(err),
+            PeerDependenciesLintErrorKind::UnsatisfiedPeerDependencies(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for PeerDependenciesLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: PeerDependenciesLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for PeerDependenciesLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: PeerDependenciesLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<PeerDependenciesLintReport> for PeerDependenciesLintError {
+    fn from(report: PeerDependenciesLintReport) -> Self {
+        Self {
+            kind: PeerDependenciesLintErrorKind::UnsatisfiedPeerDependencies(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PeerDependenciesLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    UnsatisfiedPeerDependencies(PeerDependenciesLintReport),
+}
+
+/// A peer dependency of an internal dependency which a package does not
+/// satisfy.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PeerDependencyIssue {
+    /// The package declares a version of the peer outside the required range.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Unmet {
+        peer: String,
+        required: String,
+        required_by: String,
+        actual: String,
+    },
+    /// The package does not declare the peer.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    Missing {
+        peer: String,
+        required: String,
+        required_by: String,
+    },
+}
+
+impl Display for PeerDependencyIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerDependencyIssue::Unmet {
+                peer,
+                required,
+                required_by,
+                actual,
+            } => write!(
+                f,
+                "{} requires peer {}@{} but found version {}",
+                required_by, peer, required, actual
+            ),
+            PeerDependencyIssue::Missing {
+                peer,
+                required,
+                required_by,
+            } => write!(
+                f,
+                "{} requires peer {}@{} which is not declared",
+                required_by, peer, required
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackagePeerDependencyIssues {
+    pub name: String,
+    pub path: PathBuf,
+    pub issues: Vec<PeerDependencyIssue>,
+}
+
+impl Display for PackagePeerDependencyIssues {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "File contains unsatisfied peer dependencies: {:?}",
+            self.path
+        )?;
+        for issue in self.issues.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct PeerDependenciesLintReport(Vec<PackagePeerDependencyIssues>);
+
+impl PeerDependenciesLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackagePeerDependencyIssues> {
+        self.0.iter()
+    }
+}
+
+impl Display for PeerDependenciesLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for package in self.0.iter() {
+            write!(f, "{}", package)?;
+        }
+        Ok(())
+    }
+}
+
+/// The range of versions a specifier installs, or `None` when it cannot be
+/// evaluated, such as a dist-tag or a local path. Workspace specifiers
+/// resolve to the version of the internal package.
+fn specifier_range(specifier: &str, internal_version: Option<&str>) -> Option<Range> {
+    let range = match DependencySpecifier::parse(specifier) {
+        DependencySpecifier::Range(range) => range,
+        DependencySpecifier::Alias { specifier, .. } => specifier,
+        DependencySpecifier::Workspace(range) => match (range.as_str(), internal_version) {
+            ("*" | "", Some(version)) => version.to_owned(),
+            ("^" | "~", Some(version)) => format!("{}{}", range, version),
+            ("*" | "" | "^" | "~", None) => return None,
+            _ => range,
+        },
+        _ => return None,
+    };
+    Range::parse(&range).ok()
+}
+
+/// Whether the dependency `peer` of `package_manifest` is optional.
+fn is_optional_peer(package_manifest: &PackageManifest, peer: &str) -> bool {
+    package_manifest
+        .contents
+        .extra_fields
+        .get("peerDependenciesMeta")
+        .and_then(|meta| meta.get(peer))
+        .and_then(|meta| meta.get("optional"))
+        .and_then(serde_json::Value::as_bool)
+        .unwrap_or_default()
+}
+
+/// Check the peer dependencies of every internal dependency of a package,
+/// direct and transitive, against the versions the package declares. A
+/// transitive peer is only required of the package when no package between
+/// them declares it, since that package then either provides the peer or
+/// forwards it as its own peer dependency.
+fn peer_dependency_issues(
+    package_manifest: &PackageManifest,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Vec<PeerDependencyIssue> {
+    let internal_version = |name: &str| {
+        package_manifests_by_package_name
+            .get(name)
+            .map(|package_manifest| package_manifest.contents.version.as_str())
+    };
+
+    let mut issues = Vec::new();
+    let mut seen_package_names = HashSet::new();
+    seen_package_names.insert(package_manifest.contents.name.as_str());
+    // Each internal dependency to visit, with the dependencies declared by
+    // the packages between it and `package_manifest`
+    let mut to_visit: VecDeque<(&PackageManifest, HashSet<&str>)> = package_manifest
+        .internal_dependencies_iter(package_manifests_by_package_name)
+        .map(|dependency| (dependency, HashSet::new()))
+        .collect();
+
+    while let Some((dependency, declared_between)) = to_visit.pop_front() {
+        if !seen_package_names.insert(dependency.contents.name.as_str()) {
+            continue;
+        }
+
+        let peer_dependencies = dependency
+            .contents
+            .extra_fields
+            .get("peerDependencies")
+            .and_then(serde_json::Value::as_object);
+        for (peer, required) in peer_dependencies.into_iter().flatten() {
+            let required = match required.as_str() {
+                Some(required) => required,
+                None => continue,
+            };
+            if peer == &package_manifest.contents.name || declared_between.contains(peer.as_str()) {
+                continue;
+            }
+            match package_manifest.get_dependency_version(peer) {
+                Some(actual) => {
+                    let required_range = specifier_range(required, internal_version(peer));
+                    let actual_range = specifier_range(&actual, internal_version(peer));
+                    if let (Some(required_range), Some(actual_range)) =
+                        (required_range, actual_range)
+                    {
+                        if required_range.intersect(&actual_range).is_none() {
+                            issues.push(PeerDependencyIssue::Unmet {
+                                peer: peer.to_owned(),
+                                required: required.to_owned(),
+                                required_by: dependency.contents.name.to_owned(),
+                                actual,
+                            });
+                        }
+                    }
+                }
+                None if is_optional_peer(dependency, peer) => {}
+                None => issues.push(PeerDependencyIssue::Missing {
+                    peer: peer.to_owned(),
+                    required: required.to_owned(),
+                    required_by: dependency.contents.name.to_owned(),
+                }),
+            }
+        }
+
+        let mut declared_between = declared_between;
+        declared_between.extend(
+            dependency
+                .dependencies_iter()
+                .map(|(name, _)| name.as_str()),
+        );
+        for transitive_dependency in
+            dependency.internal_dependencies_iter(package_manifests_by_package_name)
+        {
+            to_visit.push_back((transitive_dependency, declared_between.clone()));
+        }
+    }
+
+    issues.sort_unstable_by_key(|issue| issue.to_string());
+    issues
+}
+
+/// Check that every internal package satisfies the peer dependencies of its
+/// internal dependencies, direct and transitive.
+pub fn lint_report<P>(root: P) -> Result<PeerDependenciesLintReport, PeerDependenciesLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<PeerDependenciesLintReport, PeerDependenciesLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut packages: Vec<PackagePeerDependencyIssues> = package_manifests_by_package_name
+            .values()
+            .filter_map(|package_manifest| {
+                let issues =
+                    peer_dependency_issues(package_manifest, &package_manifests_by_package_name);
+                match issues.is_empty() {
+                    true => None,
+                    false => Some(PackagePeerDependencyIssues {
+                        name: package_manifest.contents.name.to_owned(),
+                        path: package_manifest.path(),
+                        issues,
+                    }),
+                }
+            })
+            .collect();
+        // Sort by path for deterministic output
+        packages.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(PeerDependenciesLintReport(packages))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), PeerDependenciesLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
@@ -0,0 +1,407 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::FromFileError;
+use crate::monorepo_configuration::MonorepoConfiguration;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::typescript_config::{ResolvedTypescriptConfig, TypescriptConfig};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct TsconfigLintError {
+    pub kind: TsconfigLintErrorKind,
+}
+
+impl Display for TsconfigLintError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            TsconfigLintErrorKind::InconsistentProjects(report) => {
+                writeln!(f, "found inconsistent TypeScript project settings\n")?;
+                write!(f, "{}", report)
+            }
+            _ => write!(f, "error linting TypeScript project settings"),
+        }
+    }
+}
+
+impl std::error::Error for TsconfigLintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            TsconfigLintErrorKind::EnumeratePackageManifests(err) => Some(err),
+            TsconfigLintErrorKind::FromFile(err) => Some(err),
+            TsconfigLintErrorKind::InconsistentProjects(_) => None,
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for TsconfigLintError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: TsconfigLintErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<FromFileError> for TsconfigLintError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: TsconfigLintErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<TsconfigLintReport> for TsconfigLintError {
+    fn from(report: TsconfigLintReport) -> Self {
+        Self {
+            kind: TsconfigLintErrorKind::InconsistentProjects(report),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TsconfigLintErrorKind {
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    InconsistentProjects(TsconfigLintReport),
+}
+
+/// An inconsistency between the settings of TypeScript projects. Projects are
+/// identified by the path to their tsconfig file from the monorepo root.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TsconfigIssue {
+    /// Two projects emit into the same (or a nested) directory.
+    #[non_exhaustive]
+    OverlappingOutDir {
+        project: PathBuf,
+        out_dir: PathBuf,
+        other_project: PathBuf,
+        other_out_dir: PathBuf,
+    },
+    /// A project's inputs contain the output of a project.
+    #[non_exhaustive]
+    OutDirIncluded {
+        project: PathBuf,
+        out_dir: PathBuf,
+        including_project: PathBuf,
+        include: PathBuf,
+    },
+    /// A project includes files outside of its `rootDir`.
+    #[non_exhaustive]
+    IncludeOutsideRootDir {
+        project: PathBuf,
+        include: PathBuf,
+        root_dir: PathBuf,
+    },
+}
+
+impl Display for TsconfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TsconfigIssue::OverlappingOutDir {
+                project,
+                out_dir,
+                other_project,
+                other_out_dir,
+            } => write!(
+                f,
+                "{:?}: outDir {:?} overlaps outDir {:?} of {:?}",
+                project, out_dir, other_out_dir, other_project
+            ),
+            TsconfigIssue::OutDirIncluded {
+                project,
+                out_dir,
+                including_project,
+                include,
+            } => write!(
+                f,
+                "{:?}: include {:?} contains outDir {:?} of {:?}",
+                including_project, include, out_dir, project
+            ),
+            TsconfigIssue::IncludeOutsideRootDir {
+                project,
+                include,
+                root_dir,
+            } => write!(
+                f,
+                "{:?}: include {:?} is outside rootDir {:?}",
+                project, include, root_dir
+            ),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TsconfigLintReport(Vec<TsconfigIssue>);
+
+impl TsconfigLintReport {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &TsconfigIssue> {
+        self.0.iter()
+    }
+}
+
+impl Display for TsconfigLintReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for issue in self.0.iter() {
+            writeln!(f, "\t{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Directories excluded by the TypeScript compiler when `exclude` is not specified.
+const DEFAULT_EXCLUDE: [&str; 3] = ["node_modules", "bower_components", "jspm_packages"];
+
+fn is_glob(component: &std::path::Component) -> bool {
+    component
+        .as_os_str()
+        .to_str()
+        .map(|s| s.contains(['*', '?', '[', '{']))
+        .unwrap_or(false)
+}
+
+/// Longest leading path of a pattern that contains no wildcards.
+fn glob_base(pattern: &Path) -> PathBuf {
+    pattern
+        .components()
+        .take_while(|component| !is_glob(component))
+        .collect()
+}
+
+/// The directory excluded by a pattern, when the pattern excludes an entire
+/// directory (such as `dist` or `dist/**/*`).
+fn excluded_directory(pattern: &Path) -> Option<PathBuf> {
+    let mut components: Vec<_> = pattern.components().collect();
+    while let Some(last) = components.last() {
+        match last.as_os_str().to_str() {
+            Some("**") | Some("*") => {
+                components.pop();
+            }
+            _ => break,
+        }
+    }
+    match components.iter().any(is_glob) {
+        true => None,
+        false => Some(components.into_iter().collect()),
+    }
+}
+
+fn overlaps(a: &Path, b: &Path) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+#[derive(Debug)]
+struct Project {
+    path: PathBuf,
+    config: ResolvedTypescriptConfig,
+}
+
+impl Project {
+    fn directory(&self) -> &Path {
+        self.path.parent().unwrap_or_else(|| Path::new(""))
+    }
+
+    fn out_dir(&self) -> Option<&Path> {
+        match self.config.is_enabled("noEmit") {
+            true => None,
+            false => self.config.out_dir.as_deref(),
+        }
+    }
+
+    fn explicit_inputs(&self) -> impl Iterator<Item = &PathBuf> {
+        self.config
+            .include
+            .iter()
+            .flatten()
+            .chain(self.config.files.iter().flatten())
+    }
+
+    /// Directories (or files) the compiler searches for input files.
+    fn input_bases(&self) -> Vec<PathBuf> {
+        match (&self.config.include, &self.config.files) {
+            (None, None) => vec![self.directory().to_owned()],
+            _ => self.explicit_inputs().map(|path| glob_base(path)).collect(),
+        }
+    }
+
+    fn excludes(&self, path: &Path) -> bool {
+        match &self.config.exclude {
+            Some(exclude) => exclude
+                .iter()
+                .filter_map(|pattern| excluded_directory(pattern))
+                .any(|directory| path.starts_with(directory)),
+            None => {
+                DEFAULT_EXCLUDE
+                    .iter()
+                    .any(|directory| path.starts_with(self.directory().join(directory)))
+                    || [&self.config.out_dir, &self.config.declaration_dir]
+                        .into_iter()
+                        .flatten()
+                        .any(|output_directory| path.starts_with(output_directory))
+            }
+        }
+    }
+}
+
+fn find_issues(projects: &[Project]) -> Vec<TsconfigIssue> {
+    let mut issues = Vec::new();
+
+    for (index, project) in projects.iter().enumerate() {
+        let out_dir = match project.out_dir() {
+            Some(out_dir) => out_dir,
+            None => continue,
+        };
+        for other_project in projects.iter().skip(index + 1) {
+            match other_project.out_dir() {
+                Some(other_out_dir) if overlaps(out_dir, other_out_dir) => {
+                    issues.push(TsconfigIssue::OverlappingOutDir {
+                        project: project.path.to_owned(),
+                        out_dir: out_dir.to_owned(),
+                        other_project: other_project.path.to_owned(),
+                        other_out_dir: other_out_dir.to_owned(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for project in projects.iter() {
+        let out_dir = match project.out_dir() {
+            Some(out_dir) => out_dir,
+            None => continue,
+        };
+        for including_project in projects.iter() {
+            if including_project.excludes(out_dir) {
+                continue;
+            }
+            if let Some(include) = including_project
+                .input_bases()
+                .into_iter()
+                .find(|input| overlaps(out_dir, input))
+            {
+                issues.push(TsconfigIssue::OutDirIncluded {
+                    project: project.path.to_owned(),
+                    out_dir: out_dir.to_owned(),
+                    including_project: including_project.path.to_owned(),
+                    include,
+                })
+            }
+        }
+    }
+
+    for project in projects.iter() {
+        let root_dir = match &project.config.root_dir {
+            Some(root_dir) => root_dir,
+            None => continue,
+        };
+        for input in project.explicit_inputs() {
+            let include = glob_base(input);
+            if !overlaps(&include, root_dir) {
+                issues.push(TsconfigIssue::IncludeOutsideRootDir {
+                    project: project.path.to_owned(),
+                    include,
+                    root_dir: root_dir.to_owned(),
+                })
+            }
+        }
+    }
+
+    issues
+}
+
+/// Enumerate inconsistencies between the `rootDir`, `outDir`, `include` and
+/// `exclude` settings of every TypeScript project in the monorepo.
+pub fn lint_report<P>(root: P) -> Result<TsconfigLintReport, TsconfigLintError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<TsconfigLintReport, TsconfigLintError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let configuration = MonorepoConfiguration::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+
+        let mut projects =
+            package_manifests_by_package_name
+                .values()
+                .flat_map(|package_manifest| {
+                    let package_directory = package_manifest.directory();
+                    std::iter::once(package_directory.clone()).chain(
+                        configuration.link.additional_configs.iter().map(
+                            move |additional_config| {
+                                package_directory.join(&additional_config.directory)
+                            },
+                        ),
+                    )
+                })
+                // Packages are not required to contain TypeScript
+                .filter(|directory| {
+                    root.join(directory)
+                        .join(TypescriptConfig::FILENAME)
+                        .is_file()
+                })
+                .map(|directory| -> Result<Project, FromFileError> {
+                    let tsconfig = TypescriptConfig::from_directory(root, &directory)?;
+                    Ok(Project {
+                        path: tsconfig.path(),
+                        config: tsconfig.resolve(root)?,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+        // Sort by path for deterministic output
+        projects.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(TsconfigLintReport(find_issues(&projects)))
+    }
+    inner(root.as_ref())
+}
+
+pub fn lint<P>(root: P) -> Result<(), TsconfigLintError>
+where
+    P: AsRef<Path>,
+{
+    let report = lint_report(root)?;
+    match report.is_empty() {
+        true => Ok(()),
+        false => Err(report)?,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{excluded_directory, glob_base};
+
+    #[test]
+    fn should_find_glob_base() {
+        assert_eq!(
+            PathBuf::from("packages/a/src"),
+            glob_base(Path::new("packages/a/src/**/*.ts"))
+        );
+        assert_eq!(
+            PathBuf::from("packages/a"),
+            glob_base(Path::new("packages/a/*.ts"))
+        );
+    }
+
+    #[test]
+    fn should_find_excluded_directory() {
+        assert_eq!(
+            Some(PathBuf::from("packages/a/dist")),
+            excluded_directory(Path::new("packages/a/dist/**/*"))
+        );
+        assert_eq!(
+            None,
+            excluded_directory(Path::new("packages/a/**/*.test.ts"))
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::monorepo_configuration::DependencyVersionConfiguration;
+use crate::package_manifest::{DependencyGroup, PackageManifest};
+use crate::semver::{Range, Version};
+
+/// A dependency declaration violating the version policy configured in
+/// `monorepo.json`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PolicyViolation {
+    /// The declared version is not among the allowed versions or ranges.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    DisallowedVersion {
+        path: PathBuf,
+        dependency: String,
+        group: String,
+        actual: String,
+        allowed: Vec<String>,
+    },
+    /// The dependency may not be used at all.
+    #[non_exhaustive]
+    #[serde(rename_all = "camelCase")]
+    BannedDependency {
+        path: PathBuf,
+        dependency: String,
+        group: String,
+        reason: String,
+    },
+}
+
+impl Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyViolation::DisallowedVersion {
+                path,
+                dependency,
+                group,
+                actual,
+                allowed,
+            } => write!(
+                f,
+                "In {:?}, {} in {} is {} but the policy allows {:?}",
+                path, dependency, group, actual, allowed
+            ),
+            PolicyViolation::BannedDependency {
+                path,
+                dependency,
+                group,
+                reason,
+            } => write!(
+                f,
+                "In {:?}, {} in {} is banned: {}",
+                path, dependency, group, reason
+            ),
+        }
+    }
+}
+
+/// Whether a declared version is one of the allowed versions, a version
+/// satisfying one of the allowed ranges, or a range lying within one.
+fn is_allowed(declared: &str, allowed: &[String]) -> bool {
+    allowed.iter().any(|allowed_version| {
+        if allowed_version == declared {
+            return true;
+        }
+        let allowed_range = match Range::parse(allowed_version) {
+            Ok(allowed_range) => allowed_range,
+            Err(_) => return false,
+        };
+        match Version::parse(declared) {
+            Ok(version) => allowed_range.satisfies(&version),
+            Err(_) => Range::parse(declared)
+                .is_ok_and(|declared_range| declared_range.is_subset(&allowed_range)),
+        }
+    })
+}
+
+/// Check every dependency selected by `is_in_scope`, in every dependency
+/// group of every package, against the version policy.
+pub(crate) fn policy_violations<F>(
+    package_manifest_by_package_name: &HashMap<String, PackageManifest>,
+    policy: &DependencyVersionConfiguration,
+    is_in_scope: F,
+) -> Vec<PolicyViolation>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut package_manifests: Vec<_> = package_manifest_by_package_name.values().collect();
+    // Sort by path for deterministic output
+    package_manifests.sort_unstable_by_key(|package_manifest| package_manifest.path());
+
+    let mut violations = Vec::new();
+    for package_manifest in package_manifests {
+        let package_name = &package_manifest.contents.name;
+        for dependency_group in DependencyGroup::VALUES {
+            let dependencies = match package_manifest
+                .contents
+                .extra_fields
+                .get(dependency_group)
+                .and_then(serde_json::Value::as_object)
+            {
+                Some(dependencies) => dependencies,
+                None => continue,
+            };
+            for (dependency, dependency_version) in dependencies {
+                if !is_in_scope(dependency) {
+                    continue;
+                }
+                if let Some(reason) = policy.ban_reason(package_name, dependency) {
+                    violations.push(PolicyViolation::BannedDependency {
+                        path: package_manifest.path(),
+                        dependency: dependency.to_owned(),
+                        group: dependency_group.to_owned(),
+                        reason: reason.to_owned(),
+                    });
+                    continue;
+                }
+                let (allowed, declared) = match (
+                    policy.allowed_versions(package_name, dependency),
+                    dependency_version.as_str(),
+                ) {
+                    (Some(allowed), Some(declared)) => (allowed, declared),
+                    _ => continue,
+                };
+                if !is_allowed(declared, allowed) {
+                    violations.push(PolicyViolation::DisallowedVersion {
+                        path: package_manifest.path(),
+                        dependency: dependency.to_owned(),
+                        group: dependency_group.to_owned(),
+                        actual: declared.to_owned(),
+                        allowed: allowed.to_vec(),
+                    });
+                }
+            }
+        }
+    }
+    violations
+}
+
+/// Rewrite every disallowed version of a dependency selected by
+/// `is_in_scope` to the first allowed version. Banned dependencies are left
+/// for the user to replace. Returns the names of the modified packages.
+pub(crate) fn apply_policy<F>(
+    package_manifest_by_package_name: &mut HashMap<String, PackageManifest>,
+    policy: &DependencyVersionConfiguration,
+    is_in_scope: F,
+) -> HashSet<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut dirty_package_names = HashSet::new();
+    for (package_name, package_manifest) in package_manifest_by_package_name.iter_mut() {
+        for dependency_group in DependencyGroup::VALUES {
+            let dependencies = match package_manifest
+                .contents
+                .extra_fields
+                .get_mut(dependency_group)
+                .and_then(serde_json::Value::as_object_mut)
+            {
+                Some(dependencies) => dependencies,
+                None => continue,
+            };
+            for (dependency, dependency_version) in dependencies.iter_mut() {
+                if !is_in_scope(dependency) {
+                    continue;
+                }
+                let (allowed, declared) = match (
+                    policy.allowed_versions(package_name, dependency),
+                    dependency_version.as_str(),
+                ) {
+                    (Some(allowed), Some(declared)) => (allowed, declared),
+                    _ => continue,
+                };
+                if let Some(first_allowed) = allowed.first() {
+                    if !is_allowed(declared, allowed) {
+                        *dependency_version = first_allowed.to_owned().into();
+                        dirty_package_names.insert(package_name.to_owned());
+                    }
+                }
+            }
+        }
+    }
+    dirty_package_names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_allowed;
+
+    #[test]
+    fn should_allow_versions_satisfying_allowed_ranges() {
+        let allowed = vec![String::from("^18.2.0"), String::from("17.x")];
+        assert!(is_allowed("^18.2.0", &allowed));
+        assert!(is_allowed("18.3.1", &allowed));
+        assert!(is_allowed("17.0.2", &allowed));
+        assert!(is_allowed("^18.3.0", &allowed));
+        assert!(is_allowed("~17.0.1", &allowed));
+        assert!(!is_allowed("^18.0.0", &allowed));
+        assert!(!is_allowed("16.14.0", &allowed));
+    }
+}
@@ -0,0 +1,892 @@
+//! Read-only access to the lockfile of a monorepo, written by npm, Yarn or
+//! pnpm, to find which version of each dependency is installed.
+
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::{read_json_from_file, read_to_string, FromFileError};
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct LockfileError {
+    /// Path to the lockfile, or to the monorepo root when no lockfile was
+    /// found.
+    pub path: PathBuf,
+    pub kind: LockfileErrorKind,
+}
+
+impl Display for LockfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            LockfileErrorKind::NotFound => write!(
+                f,
+                "no package-lock.json, yarn.lock or pnpm-lock.yaml found in {:?}",
+                self.path
+            ),
+            LockfileErrorKind::Unsupported(reason) => {
+                write!(f, "unsupported lockfile {:?}: {}", self.path, reason)
+            }
+            _ => write!(f, "unable to read lockfile {:?}", self.path),
+        }
+    }
+}
+
+impl std::error::Error for LockfileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            LockfileErrorKind::FromFile(err) => Some(err),
+            LockfileErrorKind::ParseYaml(err) => Some(err),
+            LockfileErrorKind::NotFound | LockfileErrorKind::Unsupported(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LockfileErrorKind {
+    #[non_exhaustive]
+    NotFound,
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    ParseYaml(serde_yaml::Error),
+    #[non_exhaustive]
+    Unsupported(String),
+}
+
+/// The package manager which wrote a lockfile.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LockfileFormat {
+    Npm,
+    YarnClassic,
+    YarnBerry,
+    Pnpm,
+}
+
+impl Display for LockfileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockfileFormat::Npm => write!(f, "npm"),
+            LockfileFormat::YarnClassic => write!(f, "yarn classic"),
+            LockfileFormat::YarnBerry => write!(f, "yarn berry"),
+            LockfileFormat::Pnpm => write!(f, "pnpm"),
+        }
+    }
+}
+
+/// How a dependency is installed according to a lockfile.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Resolution {
+    /// A version fetched from the registry.
+    Version(String),
+    /// A symbolic link to a local directory, such as a workspace package.
+    Link,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmLockfile {
+    lockfile_version: u64,
+    #[serde(default)]
+    packages: HashMap<String, NpmLockfilePackage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct NpmLockfilePackage {
+    version: Option<String>,
+    /// Key of the linked package, such as `packages/b`, for links.
+    resolved: Option<String>,
+    #[serde(default)]
+    link: bool,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: HashMap<String, String>,
+}
+
+/// An entry of a Yarn lockfile, classic or Berry.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YarnLockfileEntry {
+    version: Option<String>,
+    resolution: Option<String>,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, String>,
+    link_type: Option<String>,
+}
+
+impl YarnLockfileEntry {
+    fn resolution(&self) -> Option<Resolution> {
+        let is_workspace = self
+            .resolution
+            .as_deref()
+            .is_some_and(|resolution| resolution.contains("@workspace:"));
+        if is_workspace || self.link_type.as_deref() == Some("soft") {
+            return Some(Resolution::Link);
+        }
+        self.version.clone().map(Resolution::Version)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PnpmLockfile {
+    lockfile_version: serde_yaml::Value,
+    #[serde(default)]
+    importers: HashMap<String, PnpmImporter>,
+    /// Lockfiles of a single project, before pnpm 7, have no importers.
+    #[serde(flatten)]
+    root: PnpmImporter,
+    #[serde(default)]
+    packages: HashMap<String, PnpmPackage>,
+    /// Dependencies of each package, since lockfile version 9.
+    #[serde(default)]
+    snapshots: HashMap<String, PnpmPackage>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PnpmImporter {
+    #[serde(default)]
+    dependencies: HashMap<String, PnpmDependency>,
+    #[serde(default)]
+    dev_dependencies: HashMap<String, PnpmDependency>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, PnpmDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PnpmDependency {
+    /// Lockfile version 5, which records specifiers separately.
+    Version(String),
+    /// Lockfile version 6 and later.
+    Resolved { version: String },
+}
+
+impl PnpmDependency {
+    fn version(&self) -> &str {
+        match self {
+            PnpmDependency::Version(version) => version,
+            PnpmDependency::Resolved { version } => version,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PnpmPackage {
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug)]
+enum LockfileContents {
+    Npm(NpmLockfile),
+    Yarn {
+        entries: Vec<YarnLockfileEntry>,
+        /// Index into `entries` by descriptor, such as `lodash@^4.17.0` or,
+        /// with Berry, `lodash@npm:^4.17.0`.
+        entries_by_descriptor: HashMap<String, usize>,
+        berry: bool,
+    },
+    Pnpm {
+        lockfile: Box<PnpmLockfile>,
+        /// Whether the lockfile predates version 6, which changed the format
+        /// of package keys and peer suffixes.
+        legacy: bool,
+    },
+}
+
+/// A package installed according to a lockfile.
+#[derive(Debug)]
+struct LockfileNode {
+    name: String,
+    resolution: Resolution,
+    /// Keys of the installed dependencies of the package.
+    dependencies: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Lockfile {
+    path: PathBuf,
+    contents: LockfileContents,
+}
+
+/// Name of the package in a descriptor such as `@scope/name@^1.0.0`.
+fn descriptor_name(descriptor: &str) -> &str {
+    // Skip the leading `@` of scoped package names
+    match descriptor.get(1..).and_then(|rest| rest.find('@')) {
+        Some(index) => &descriptor[..index + 1],
+        None => descriptor,
+    }
+}
+
+/// Split a line of a Yarn classic lockfile, such as `"@scope/a" "^1.0.0"`,
+/// into its key and value.
+fn yarn_classic_field(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    let (key, value) = match line.strip_prefix('"') {
+        Some(rest) => rest.split_once('"')?,
+        None => line.split_once(' ')?,
+    };
+    Some((key, value.trim().trim_matches('"')))
+}
+
+fn parse_yarn_classic(contents: &str) -> LockfileContents {
+    let mut entries: Vec<YarnLockfileEntry> = Vec::new();
+    let mut entries_by_descriptor = HashMap::new();
+    let mut in_optional_dependencies = None;
+    for line in contents.lines() {
+        if line.starts_with('#') || line.trim().is_empty() {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            for descriptor in line.trim_end().trim_end_matches(':').split(", ") {
+                entries_by_descriptor
+                    .insert(descriptor.trim_matches('"').to_owned(), entries.len());
+            }
+            entries.push(YarnLockfileEntry::default());
+            in_optional_dependencies = None;
+            continue;
+        }
+        let entry = match entries.last_mut() {
+            Some(entry) => entry,
+            None => continue,
+        };
+        // Fields of the entry are indented by two spaces, and its
+        // dependencies by four
+        if !line.starts_with("   ") {
+            in_optional_dependencies = match line.trim() {
+                "dependencies:" => Some(false),
+                "optionalDependencies:" => Some(true),
+                _ => None,
+            };
+            if let Some(("version", version)) = yarn_classic_field(line) {
+                entry.version = Some(version.to_owned());
+            }
+            continue;
+        }
+        let dependencies = match in_optional_dependencies {
+            Some(false) => &mut entry.dependencies,
+            Some(true) => &mut entry.optional_dependencies,
+            None => continue,
+        };
+        if let Some((name, range)) = yarn_classic_field(line) {
+            dependencies.insert(name.to_owned(), range.to_owned());
+        }
+    }
+    LockfileContents::Yarn {
+        entries,
+        entries_by_descriptor,
+        berry: false,
+    }
+}
+
+fn parse_yarn_berry(path: &Path, contents: &str) -> Result<LockfileContents, LockfileError> {
+    let parse_error = |err| LockfileError {
+        path: path.to_owned(),
+        kind: LockfileErrorKind::ParseYaml(err),
+    };
+    let document: HashMap<String, serde_yaml::Value> =
+        serde_yaml::from_str(contents).map_err(parse_error)?;
+
+    let mut entries = Vec::new();
+    let mut entries_by_descriptor = HashMap::new();
+    for (descriptors, entry) in document {
+        if descriptors == "__metadata" {
+            continue;
+        }
+        let entry: YarnLockfileEntry = serde_yaml::from_value(entry).map_err(parse_error)?;
+        for descriptor in descriptors.split(", ") {
+            entries_by_descriptor.insert(descriptor.trim().to_owned(), entries.len());
+        }
+        entries.push(entry);
+    }
+    Ok(LockfileContents::Yarn {
+        entries,
+        entries_by_descriptor,
+        berry: true,
+    })
+}
+
+fn parse_pnpm(path: &Path, contents: &str) -> Result<LockfileContents, LockfileError> {
+    let lockfile: PnpmLockfile = serde_yaml::from_str(contents).map_err(|err| LockfileError {
+        path: path.to_owned(),
+        kind: LockfileErrorKind::ParseYaml(err),
+    })?;
+    let lockfile_version = match &lockfile.lockfile_version {
+        serde_yaml::Value::Number(number) => number.as_f64(),
+        serde_yaml::Value::String(string) => string.parse().ok(),
+        _ => None,
+    };
+    let legacy = match lockfile_version {
+        Some(lockfile_version) => lockfile_version < 6.0,
+        None => {
+            return Err(LockfileError {
+                path: path.to_owned(),
+                kind: LockfileErrorKind::Unsupported(String::from(
+                    "missing or invalid lockfileVersion",
+                )),
+            })
+        }
+    };
+    Ok(LockfileContents::Pnpm {
+        lockfile: Box::new(lockfile),
+        legacy,
+    })
+}
+
+/// The resolution of a version recorded by pnpm for an importer, such as
+/// `1.2.3(react@18.2.0)`, `/alias@1.2.3` or `link:../a`.
+fn pnpm_resolution(version: &str, legacy: bool) -> Resolution {
+    if version.starts_with("link:") || version.starts_with("file:") {
+        return Resolution::Link;
+    }
+    let mut version = version.split('(').next().unwrap_or_default();
+    if legacy {
+        version = version.split('_').next().unwrap_or_default();
+    }
+    let version = match version.rsplit_once('@') {
+        Some((_, version)) => version,
+        None if legacy && version.starts_with('/') => {
+            version.rsplit('/').next().unwrap_or_default()
+        }
+        None => version,
+    };
+    Resolution::Version(version.to_owned())
+}
+
+/// The package name and version of a key of the pnpm `packages` section,
+/// such as `/name@1.2.3(peer@1.0.0)`, `name@1.2.3` or `/name/1.2.3_peer@1.0.0`.
+fn pnpm_package_key(key: &str, legacy: bool) -> Option<(&str, &str)> {
+    let key = key.strip_prefix('/').unwrap_or(key);
+    if legacy {
+        let (name, version) = key.rsplit_once('/')?;
+        return Some((name, version.split('_').next()?));
+    }
+    let key = key.split('(').next()?;
+    let name = descriptor_name(key);
+    Some((name, key.get(name.len() + 1..)?))
+}
+
+/// Key in the npm `packages` section of the dependency `name` as required by
+/// the package at `from`, following the node_modules resolution algorithm up
+/// to the monorepo root.
+fn npm_package_key(lockfile: &NpmLockfile, from: &str, name: &str) -> Option<String> {
+    Path::new(from).ancestors().find_map(|ancestor| {
+        let key = match ancestor.as_os_str().is_empty() {
+            true => format!("node_modules/{}", name),
+            false => format!("{}/node_modules/{}", ancestor.display(), name),
+        };
+        lockfile.packages.contains_key(&key).then_some(key)
+    })
+}
+
+/// Key of the Yarn entry for the dependency `name` declared with `range`.
+/// Berry prefixes ranges with their protocol, such as `npm:`, in descriptors,
+/// but omits it in the dependencies of entries before Yarn 4.
+fn yarn_descriptor(
+    entries_by_descriptor: &HashMap<String, usize>,
+    name: &str,
+    range: &str,
+) -> Option<String> {
+    [
+        format!("{}@{}", name, range),
+        format!("{}@npm:{}", name, range),
+    ]
+    .into_iter()
+    .find(|descriptor| entries_by_descriptor.contains_key(descriptor))
+}
+
+/// Key of the pnpm package installed for the dependency `name` at `version`,
+/// as recorded by the package depending on it.
+fn pnpm_snapshot_key(lockfile: &PnpmLockfile, name: &str, version: &str) -> Option<String> {
+    if version.starts_with("link:") || version.starts_with("file:") {
+        return None;
+    }
+    [
+        format!("{}@{}", name, version),
+        format!("/{}@{}", name, version),
+        format!("/{}/{}", name, version),
+        // Aliases record the installed package with its version
+        version.to_owned(),
+    ]
+    .into_iter()
+    .find(|key| lockfile.snapshots.contains_key(key) || lockfile.packages.contains_key(key))
+}
+
+/// Directory of the importer `link` points to, such as `packages/b` for
+/// `../b` from `packages/a`, or `None` when it is outside the monorepo.
+fn linked_directory(directory: &str, link: &str) -> Option<String> {
+    let mut components: Vec<&str> = directory
+        .split('/')
+        .filter(|component| !component.is_empty())
+        .collect();
+    for component in link.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop()?;
+            }
+            component => components.push(component),
+        }
+    }
+    Some(components.join("/"))
+}
+
+/// Normalize a package directory, relative to the monorepo root, into the
+/// form npm uses in its lockfile, where the root is the empty string.
+fn importer_directory(directory: &Path) -> String {
+    let directory = directory.to_string_lossy().replace('\\', "/");
+    let directory = directory.trim_start_matches("./").trim_end_matches('/');
+    match directory {
+        "." => String::new(),
+        directory => directory.to_owned(),
+    }
+}
+
+impl Lockfile {
+    /// Read the lockfile of the monorepo at `root`, preferring
+    /// `package-lock.json`, then `yarn.lock`, then `pnpm-lock.yaml`.
+    pub fn from_directory<P>(root: P) -> Result<Self, LockfileError>
+    where
+        P: AsRef<Path>,
+    {
+        fn inner(root: &Path) -> Result<Lockfile, LockfileError> {
+            let from_file_error = |err: FromFileError| LockfileError {
+                path: err.path.to_owned(),
+                kind: LockfileErrorKind::FromFile(err),
+            };
+
+            let path = root.join("package-lock.json");
+            if path.is_file() {
+                let lockfile: NpmLockfile = read_json_from_file(&path).map_err(from_file_error)?;
+                if lockfile.lockfile_version < 2 {
+                    return Err(LockfileError {
+                        path,
+                        kind: LockfileErrorKind::Unsupported(String::from(
+                            "lockfileVersion 1 is not supported, run npm install with npm 7 or later",
+                        )),
+                    });
+                }
+                return Ok(Lockfile {
+                    path,
+                    contents: LockfileContents::Npm(lockfile),
+                });
+            }
+
+            let path = root.join("yarn.lock");
+            if path.is_file() {
+                let contents = read_to_string(&path).map_err(from_file_error)?;
+                let contents = match contents.contains("\n__metadata:") {
+                    true => parse_yarn_berry(&path, &contents)?,
+                    false => parse_yarn_classic(&contents),
+                };
+                return Ok(Lockfile { path, contents });
+            }
+
+            let path = root.join("pnpm-lock.yaml");
+            if path.is_file() {
+                let contents = read_to_string(&path).map_err(from_file_error)?;
+                let contents = parse_pnpm(&path, &contents)?;
+                return Ok(Lockfile { path, contents });
+            }
+
+            Err(LockfileError {
+                path: root.to_owned(),
+                kind: LockfileErrorKind::NotFound,
+            })
+        }
+        inner(root.as_ref())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn format(&self) -> LockfileFormat {
+        match &self.contents {
+            LockfileContents::Npm(_) => LockfileFormat::Npm,
+            LockfileContents::Yarn { berry: false, .. } => LockfileFormat::YarnClassic,
+            LockfileContents::Yarn { berry: true, .. } => LockfileFormat::YarnBerry,
+            LockfileContents::Pnpm { .. } => LockfileFormat::Pnpm,
+        }
+    }
+
+    /// Whether the lockfile records links to workspace packages. Yarn classic
+    /// omits workspace packages entirely, so a dependency on one which is
+    /// missing from its lockfile is linked.
+    pub fn records_workspace_links(&self) -> bool {
+        self.format() != LockfileFormat::YarnClassic
+    }
+
+    /// The pnpm importer in `directory`.
+    fn pnpm_importer(&self, directory: &str) -> Option<&PnpmImporter> {
+        let lockfile = match &self.contents {
+            LockfileContents::Pnpm { lockfile, .. } => lockfile,
+            _ => return None,
+        };
+        let importer = match directory {
+            "" => ".",
+            directory => directory,
+        };
+        match lockfile.importers.get(importer) {
+            Some(importer) => Some(importer),
+            None if lockfile.importers.is_empty() && directory.is_empty() => Some(&lockfile.root),
+            None => None,
+        }
+    }
+
+    /// Version recorded by pnpm for the dependency `dependency` of the
+    /// importer in `directory`.
+    fn pnpm_importer_version(&self, directory: &str, dependency: &str) -> Option<&str> {
+        let importer = self.pnpm_importer(directory)?;
+        [
+            &importer.dependencies,
+            &importer.dev_dependencies,
+            &importer.optional_dependencies,
+        ]
+        .into_iter()
+        .find_map(|dependencies| dependencies.get(dependency))
+        .map(PnpmDependency::version)
+    }
+
+    /// Keys of the packages installed for the dependencies of the importers
+    /// linked, directly or transitively, from the importer in `directory`.
+    /// pnpm records links to workspace packages only in the importers.
+    fn pnpm_linked_dependency_keys(&self, directory: &str) -> Vec<String> {
+        let lockfile = match &self.contents {
+            LockfileContents::Pnpm { lockfile, .. } => lockfile,
+            _ => return Vec::new(),
+        };
+        let mut seen_directories = HashSet::from([directory.to_owned()]);
+        let mut to_visit = vec![(directory.to_owned(), true)];
+        let mut keys = Vec::new();
+        while let Some((directory, is_origin)) = to_visit.pop() {
+            let importer = match self.pnpm_importer(&directory) {
+                Some(importer) => importer,
+                None => continue,
+            };
+            // Development dependencies of linked packages are not installed
+            let dependencies = [
+                Some(&importer.dependencies),
+                is_origin.then_some(&importer.dev_dependencies),
+                Some(&importer.optional_dependencies),
+            ];
+            for (dependency, version) in dependencies.into_iter().flatten().flatten() {
+                let version = version.version();
+                match version.strip_prefix("link:") {
+                    Some(link) => {
+                        if let Some(linked) = linked_directory(&directory, link) {
+                            if seen_directories.insert(linked.clone()) {
+                                to_visit.push((linked, false));
+                            }
+                        }
+                    }
+                    None if !is_origin => {
+                        keys.extend(pnpm_snapshot_key(lockfile, dependency, version))
+                    }
+                    None => {}
+                }
+            }
+        }
+        keys
+    }
+
+    /// Key of the package installed for the dependency `dependency`, declared
+    /// as `specifier` by the package in `directory`.
+    fn dependency_key(&self, directory: &str, dependency: &str, specifier: &str) -> Option<String> {
+        match &self.contents {
+            LockfileContents::Npm(lockfile) => npm_package_key(lockfile, directory, dependency),
+            LockfileContents::Yarn {
+                entries,
+                entries_by_descriptor,
+                berry,
+            } => {
+                // Prefer the range Berry recorded for the workspace, which is
+                // the one installed even when the manifest has since changed
+                let workspace_suffix = match directory {
+                    "" => String::from("@workspace:."),
+                    directory => format!("@workspace:{}", directory),
+                };
+                let range = entries
+                    .iter()
+                    .filter(|_| *berry)
+                    .find(|entry| {
+                        entry
+                            .resolution
+                            .as_deref()
+                            .is_some_and(|resolution| resolution.ends_with(&workspace_suffix))
+                    })
+                    .and_then(|workspace| workspace.dependencies.get(dependency))
+                    .map(String::as_str)
+                    .unwrap_or(specifier);
+                yarn_descriptor(entries_by_descriptor, dependency, range)
+            }
+            LockfileContents::Pnpm { lockfile, .. } => {
+                let version = self.pnpm_importer_version(directory, dependency)?;
+                pnpm_snapshot_key(lockfile, dependency, version)
+            }
+        }
+    }
+
+    fn node(&self, key: &str) -> Option<LockfileNode> {
+        match &self.contents {
+            LockfileContents::Npm(lockfile) => {
+                let package = lockfile.packages.get(key)?;
+                let (_, name) = key.rsplit_once("node_modules/")?;
+                // Links install the dependencies of their target, such as a
+                // workspace package
+                let (resolution, from) = match package.link {
+                    true => (Resolution::Link, package.resolved.as_deref()),
+                    false => (Resolution::Version(package.version.clone()?), Some(key)),
+                };
+                let dependencies = from
+                    .and_then(|from| Some((from, lockfile.packages.get(from)?)))
+                    .map(|(from, package)| {
+                        package
+                            .dependencies
+                            .keys()
+                            .chain(package.optional_dependencies.keys())
+                            .chain(package.peer_dependencies.keys())
+                            .filter_map(|dependency| npm_package_key(lockfile, from, dependency))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                Some(LockfileNode {
+                    name: name.to_owned(),
+                    resolution,
+                    dependencies,
+                })
+            }
+            LockfileContents::Yarn {
+                entries,
+                entries_by_descriptor,
+                ..
+            } => {
+                let entry = &entries[*entries_by_descriptor.get(key)?];
+                let dependencies = entry
+                    .dependencies
+                    .iter()
+                    .chain(entry.optional_dependencies.iter())
+                    .filter_map(|(dependency, range)| {
+                        yarn_descriptor(entries_by_descriptor, dependency, range)
+                    })
+                    .collect();
+                Some(LockfileNode {
+                    name: descriptor_name(key).to_owned(),
+                    resolution: entry.resolution()?,
+                    dependencies,
+                })
+            }
+            LockfileContents::Pnpm { lockfile, legacy } => {
+                let (name, version) = pnpm_package_key(key, *legacy)?;
+                let package = lockfile
+                    .snapshots
+                    .get(key)
+                    .or_else(|| lockfile.packages.get(key))?;
+                let dependencies = package
+                    .dependencies
+                    .iter()
+                    .chain(package.optional_dependencies.iter())
+                    .filter_map(|(dependency, version)| {
+                        pnpm_snapshot_key(lockfile, dependency, version)
+                    })
+                    .collect();
+                Some(LockfileNode {
+                    name: name.to_owned(),
+                    resolution: Resolution::Version(version.to_owned()),
+                    dependencies,
+                })
+            }
+        }
+    }
+
+    /// How the dependency `dependency`, declared as `specifier` by the
+    /// package in `directory` (relative to the monorepo root), is installed,
+    /// or `None` when the lockfile does not contain it.
+    pub fn resolve(
+        &self,
+        directory: &Path,
+        dependency: &str,
+        specifier: &str,
+    ) -> Option<Resolution> {
+        let directory = importer_directory(directory);
+        if let LockfileContents::Pnpm { legacy, .. } = &self.contents {
+            // Links have no entry of their own in pnpm lockfiles
+            let version = self.pnpm_importer_version(&directory, dependency)?;
+            return Some(pnpm_resolution(version, *legacy));
+        }
+        let key = self.dependency_key(&directory, dependency, specifier)?;
+        self.node(&key).map(|node| node.resolution)
+    }
+
+    /// Every version of `package` installed from the registry for
+    /// `package_manifest`, as a direct or transitive dependency. Linked
+    /// packages, such as other internal packages, are traversed through
+    /// their own entries, which Yarn classic lockfiles do not record.
+    pub fn resolved_versions(
+        &self,
+        package_manifest: &PackageManifest,
+        package: &str,
+    ) -> BTreeSet<String> {
+        let directory = importer_directory(&package_manifest.directory());
+        let mut to_visit: VecDeque<String> = package_manifest
+            .dependencies_iter()
+            .filter_map(|(dependency, specifier)| {
+                self.dependency_key(&directory, dependency, specifier.as_str()?)
+            })
+            .chain(self.pnpm_linked_dependency_keys(&directory))
+            .collect();
+
+        let mut seen_keys = HashSet::new();
+        let mut versions = BTreeSet::new();
+        while let Some(key) = to_visit.pop_front() {
+            if !seen_keys.insert(key.clone()) {
+                continue;
+            }
+            let node = match self.node(&key) {
+                Some(node) => node,
+                None => continue,
+            };
+            if let Resolution::Version(version) = node.resolution {
+                if node.name == package {
+                    versions.insert(version);
+                }
+            }
+            to_visit.extend(node.dependencies);
+        }
+        versions
+    }
+
+    /// Every version of `package` installed from the registry, including
+    /// transitive dependencies.
+    pub fn versions(&self, package: &str) -> BTreeSet<String> {
+        match &self.contents {
+            LockfileContents::Npm(lockfile) => lockfile
+                .packages
+                .iter()
+                .filter(|(key, entry)| {
+                    !entry.link
+                        && key
+                            .rsplit_once("node_modules/")
+                            .is_some_and(|(_, name)| name == package)
+                })
+                .filter_map(|(_, entry)| entry.version.clone())
+                .collect(),
+            LockfileContents::Yarn {
+                entries,
+                entries_by_descriptor,
+                ..
+            } => entries_by_descriptor
+                .iter()
+                .filter(|(descriptor, _)| descriptor_name(descriptor) == package)
+                .filter_map(|(_, index)| match entries[*index].resolution()? {
+                    Resolution::Version(version) => Some(version),
+                    Resolution::Link => None,
+                })
+                .collect(),
+            LockfileContents::Pnpm { lockfile, legacy } => lockfile
+                .packages
+                .keys()
+                .filter_map(|key| pnpm_package_key(key, *legacy))
+                .filter(|(name, _)| *name == package)
+                .map(|(_, version)| version.to_owned())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_yarn_classic, pnpm_package_key, pnpm_resolution, Lockfile, LockfileContents,
+        Resolution,
+    };
+
+    #[test]
+    fn should_parse_yarn_classic_entries() {
+        let contents = r#"# THIS IS AN AUTOGENERATED FILE. DO NOT EDIT THIS FILE DIRECTLY.
+# yarn lockfile v1
+
+
+"@scope/a@^1.0.0", "@scope/a@^1.1.0":
+  version "1.2.0"
+  resolved "https://registry.yarnpkg.com/@scope/a/-/a-1.2.0.tgz"
+  dependencies:
+    b "^2.0.0"
+    "@scope/c" ">= 1.0.0 < 2"
+
+b@^2.0.0:
+  version "2.0.1"
+"#;
+        let lockfile = Lockfile {
+            path: Default::default(),
+            contents: parse_yarn_classic(contents),
+        };
+        let node = lockfile.node("@scope/a@^1.1.0").unwrap();
+        assert_eq!("@scope/a", node.name);
+        assert_eq!(Resolution::Version(String::from("1.2.0")), node.resolution);
+        assert_eq!(vec![String::from("b@^2.0.0")], node.dependencies);
+        match &lockfile.contents {
+            LockfileContents::Yarn { entries, .. } => assert_eq!(
+                Some(">= 1.0.0 < 2"),
+                entries[0].dependencies.get("@scope/c").map(String::as_str)
+            ),
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            vec!["2.0.1"],
+            lockfile.versions("b").iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_parse_pnpm_versions() {
+        let cases = [
+            ("1.2.3", false, Resolution::Version(String::from("1.2.3"))),
+            (
+                "1.2.3(react@18.2.0)",
+                false,
+                Resolution::Version(String::from("1.2.3")),
+            ),
+            (
+                "/b@2.0.0",
+                false,
+                Resolution::Version(String::from("2.0.0")),
+            ),
+            (
+                "1.2.3_react@18.2.0",
+                true,
+                Resolution::Version(String::from("1.2.3")),
+            ),
+            ("/b/2.0.0", true, Resolution::Version(String::from("2.0.0"))),
+            ("link:../a", false, Resolution::Link),
+        ];
+        for (version, legacy, expected) in cases {
+            assert_eq!(expected, pnpm_resolution(version, legacy), "{}", version);
+        }
+
+        assert_eq!(
+            Some(("@scope/a", "1.0.0")),
+            pnpm_package_key("/@scope/a@1.0.0(react@18.2.0)", false)
+        );
+        assert_eq!(Some(("a", "1.0.0")), pnpm_package_key("a@1.0.0", false));
+        assert_eq!(
+            Some(("@scope/a", "1.0.0")),
+            pnpm_package_key("/@scope/a/1.0.0_react@18.2.0", true)
+        );
+    }
+}
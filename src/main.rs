@@ -1,27 +1,117 @@
 #![forbid(unsafe_code)]
 
 use std::io::{self, Write};
+use std::process::ExitCode;
 
 use clap::Parser;
 
 mod little_anyhow;
 mod opts;
 
-use opts::Action;
-use typescript_tools::{link, lint, make_depend, pin, query};
+use opts::{Action, AnnotatedLintFormat, LintFormat};
+use typescript_tools::{
+    annotation, changelog, changeset, link, lint, make_depend, pin, publish, query, release,
+    version,
+};
 
 // RESUME: why is this not printing with display?
-fn main() -> Result<(), little_anyhow::Error> {
+fn main() -> Result<ExitCode, little_anyhow::Error> {
     let args = opts::Opts::parse();
 
     match args.subcommand {
         opts::ClapSubCommand::Link(args) => match args.action {
             Action::Modify => link::modify(args.root)?,
-            Action::Lint => link::lint(args.root)?,
+            Action::Lint => match args.format {
+                LintFormat::Text => link::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = link::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
         },
-        opts::ClapSubCommand::Pin(args) => match args.action {
-            Action::Modify => pin::modify(args.root)?,
-            Action::Lint => pin::lint(args.root)?,
+        opts::ClapSubCommand::Pin(args) => match (args.action, args.protocol) {
+            (Action::Modify, Some(protocol)) => pin::convert(args.root, protocol.into())?,
+            (Action::Modify, None) => pin::modify(args.root)?,
+            (Action::Lint, _) => match args.format {
+                AnnotatedLintFormat::Text => pin::lint(args.root)?,
+                format => {
+                    let report = pin::lint_report(&args.root)?;
+                    let output = match format {
+                        AnnotatedLintFormat::Github => {
+                            annotation::github(&args.root, &report.annotations())
+                        }
+                        AnnotatedLintFormat::Sarif => serde_json::to_string_pretty(
+                            &annotation::sarif(&args.root, &report.annotations(), pin::LINT_RULES),
+                        )?,
+                        _ => serde_json::to_string_pretty(&report)?,
+                    };
+                    writeln!(io::stdout(), "{}", output.trim_end())?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+        },
+        opts::ClapSubCommand::Version(args) => {
+            let bump = version::Bump::parse(&args.bump, args.preid)?;
+            let plan = match args.dry_run {
+                true => version::plan(args.root, &args.packages, &bump, args.cascade)?,
+                false => version::modify(args.root, &args.packages, &bump, args.cascade)?,
+            };
+            write!(io::stdout(), "{}", plan)?;
+        }
+        opts::ClapSubCommand::Release(args) => match args.subcommand {
+            opts::ClapReleaseSubCommand::Plan(args) => {
+                let plan = release::plan(args.root)?;
+                match args.format {
+                    opts::ReleasePlanFormat::Table => write!(io::stdout(), "{}", plan)?,
+                    opts::ReleasePlanFormat::Json => {
+                        writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&plan)?)?
+                    }
+                }
+            }
+        },
+        opts::ClapSubCommand::Changelog(args) => {
+            let changelogs = match args.dry_run {
+                true => changelog::plan(args.root)?,
+                false => changelog::modify(args.root)?,
+            };
+            write!(io::stdout(), "{}", changelogs)?;
+        }
+        opts::ClapSubCommand::Changeset(args) => match args.subcommand {
+            opts::ClapChangesetSubCommand::Add(args) => {
+                let path = changeset::add(
+                    args.root,
+                    &args.packages,
+                    args.release.into(),
+                    &args.message,
+                )?;
+                writeln!(io::stdout(), "{}", path.display())?;
+            }
+            opts::ClapChangesetSubCommand::Apply(args) => {
+                let plan = match args.dry_run {
+                    true => changeset::plan(args.root)?,
+                    false => changeset::apply(args.root)?,
+                };
+                write!(io::stdout(), "{}", plan)?;
+            }
+        },
+        opts::ClapSubCommand::Publish(args) => match args.subcommand {
+            opts::ClapPublishSubCommand::Plan(args) => {
+                let plan = publish::plan(args.root, args.registry)?;
+                match args.format {
+                    LintFormat::Text => write!(io::stdout(), "{}", plan)?,
+                    LintFormat::Json => {
+                        writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&plan)?)?
+                    }
+                }
+                if !plan.private_dependencies.is_empty() {
+                    return Ok(ExitCode::FAILURE);
+                }
+            }
         },
         opts::ClapSubCommand::MakeDepend(args) => make_depend::make_dependency_makefile(
             &args.root,
@@ -35,12 +125,90 @@ fn main() -> Result<(), little_anyhow::Error> {
                 let output = query::query_internal_dependencies(args.root, args.format.into())?;
                 writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&output)?)?;
             }
+            opts::ClapQuerySubCommand::Resolved(args) => {
+                let output = query::query_resolved(args.root, args.dependency)?;
+                writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&output)?)?;
+            }
         },
         opts::ClapSubCommand::Lint(args) => match args.subcommand {
-            opts::ClapLintSubCommand::DependencyVersion(args) => {
-                lint::lint_dependency_version(args.root, &args.dependencies)?
-            }
+            opts::ClapLintSubCommand::DependencyVersion(args) => match args.action {
+                Action::Modify => {
+                    let strategy = match (args.version, args.strategy) {
+                        (Some(version), _) => lint::DependencyVersionStrategy::Explicit(version),
+                        (None, opts::DependencyVersionStrategy::MostCommon) => {
+                            lint::DependencyVersionStrategy::MostCommon
+                        }
+                        (None, opts::DependencyVersionStrategy::Highest) => {
+                            lint::DependencyVersionStrategy::Highest
+                        }
+                    };
+                    lint::modify_dependency_version(args.root, &args.dependencies, &strategy)?
+                }
+                Action::Lint => lint::lint_dependency_version(args.root, &args.dependencies)?,
+            },
+            opts::ClapLintSubCommand::Tsconfig(args) => lint::tsconfig::lint(args.root)?,
+            opts::ClapLintSubCommand::EntryPoints(args) => lint::entry_points::lint(args.root)?,
+            opts::ClapLintSubCommand::Exports(args) => match args.format {
+                LintFormat::Text => lint::exports::lint(args.root, args.check_files)?,
+                LintFormat::Json => {
+                    let report = lint::exports::lint_report(args.root, args.check_files)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+            opts::ClapLintSubCommand::PeerDependencies(args) => match args.format {
+                LintFormat::Text => lint::peer_dependencies::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = lint::peer_dependencies::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+            opts::ClapLintSubCommand::Imports(args) => match args.format {
+                LintFormat::Text => lint::imports::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = lint::imports::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+            opts::ClapLintSubCommand::DeepImports(args) => match args.format {
+                LintFormat::Text => lint::deep_imports::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = lint::deep_imports::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+            opts::ClapLintSubCommand::Boundaries(args) => match args.format {
+                LintFormat::Text => lint::boundaries::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = lint::boundaries::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
+            opts::ClapLintSubCommand::Lockfile(args) => match args.format {
+                LintFormat::Text => lint::lockfile::lint(args.root)?,
+                LintFormat::Json => {
+                    let report = lint::lockfile::lint_report(args.root)?;
+                    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&report)?)?;
+                    if !report.is_empty() {
+                        return Ok(ExitCode::FAILURE);
+                    }
+                }
+            },
         },
     };
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -13,7 +13,6 @@ use crate::package_manifest::PackageManifest;
 
 #[derive(Template)]
 #[template(path = "makefile")]
-
 struct MakefileTemplate<'a> {
     root: &'a str,
     output_file: &'a str,
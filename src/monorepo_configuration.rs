@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::io::{read_json_from_file, FromFileError};
+use crate::lint::DependencyVersionMode;
+use crate::package_manifest::DependencyGroup;
+use crate::pin::PinMode;
+
+/// Optional configuration for the typescript-tools, read from the
+/// `monorepo.json` file in the monorepo root.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct MonorepoConfiguration {
+    #[serde(default)]
+    pub link: LinkConfiguration,
+    #[serde(default)]
+    pub pin: PinConfiguration,
+    #[serde(default)]
+    pub dependency_version: DependencyVersionConfiguration,
+    #[serde(default)]
+    pub boundaries: BoundariesConfiguration,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct LinkConfiguration {
+    /// Secondary tsconfig files to manage in every package, in addition to
+    /// the package's main tsconfig.json.
+    #[serde(default)]
+    pub additional_configs: Vec<AdditionalTypescriptConfig>,
+}
+
+/// A secondary tsconfig.json in each package, for example one compiling the
+/// package's tests. Its project references point at the package's main
+/// project and at the internal dependencies declared in `dependency_groups`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct AdditionalTypescriptConfig {
+    /// Directory containing the tsconfig.json file, relative to the package
+    /// directory.
+    pub directory: PathBuf,
+    /// Dependency groups from which to create project references.
+    #[serde(
+        default = "all_dependency_groups",
+        deserialize_with = "deserialize_dependency_groups"
+    )]
+    pub dependency_groups: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PinConfiguration {
+    /// How to pin internal dependencies in dependency groups without a mode
+    /// of their own.
+    #[serde(default)]
+    pub mode: PinMode,
+    /// Pin modes of individual dependency groups, keyed by group name.
+    #[serde(default, deserialize_with = "deserialize_dependency_group_modes")]
+    pub dependency_groups: HashMap<String, PinMode>,
+}
+
+impl PinConfiguration {
+    pub fn mode(&self, dependency_group: &str) -> PinMode {
+        self.dependency_groups
+            .get(dependency_group)
+            .copied()
+            .unwrap_or(self.mode)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct DependencyVersionConfiguration {
+    /// How declared versions of a dependency are compared for consistency.
+    #[serde(default)]
+    pub mode: DependencyVersionMode,
+    /// External dependencies exempt from the consistency check when linting
+    /// every dependency, by name or by prefix ending in `*` (like `@types/*`).
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Versions or ranges with which each external dependency may be
+    /// declared. The first entry is written when applying the policy.
+    #[serde(default)]
+    pub allowed: HashMap<String, Vec<String>>,
+    /// Dependencies which may not be used, with the reason why.
+    #[serde(default)]
+    pub banned: HashMap<String, String>,
+    /// Per-package overrides of `allowed`, keyed by package name. Listed
+    /// dependencies are also exempt from `banned` and from the consistency
+    /// check.
+    #[serde(default)]
+    pub exceptions: HashMap<String, HashMap<String, Vec<String>>>,
+}
+
+impl DependencyVersionConfiguration {
+    /// Versions or ranges `package_name` may declare for `dependency`, or
+    /// `None` when the policy does not restrict them.
+    pub fn allowed_versions(&self, package_name: &str, dependency: &str) -> Option<&[String]> {
+        self.exceptions
+            .get(package_name)
+            .and_then(|exceptions| exceptions.get(dependency))
+            .or_else(|| self.allowed.get(dependency))
+            .map(Vec::as_slice)
+    }
+
+    /// Whether `package_name` is exempt from the policy for `dependency`.
+    pub fn is_exception(&self, package_name: &str, dependency: &str) -> bool {
+        self.exceptions
+            .get(package_name)
+            .map(|exceptions| exceptions.contains_key(dependency))
+            .unwrap_or(false)
+    }
+
+    /// The reason `package_name` may not depend on `dependency`, if banned.
+    pub fn ban_reason(&self, package_name: &str, dependency: &str) -> Option<&str> {
+        match self.is_exception(package_name, dependency) {
+            true => None,
+            false => self.banned.get(dependency).map(String::as_str),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BoundariesConfiguration {
+    /// Tags of internal packages, keyed by package name, in addition to the
+    /// tags in the `monorepo` field of each package manifest.
+    #[serde(default)]
+    pub tags: HashMap<String, Vec<String>>,
+    /// Constraints on the internal dependencies of tagged packages.
+    #[serde(default)]
+    pub rules: Vec<BoundaryRule>,
+}
+
+/// A constraint on the internal dependencies of every package tagged
+/// `from`, such as "`layer:ui` may not depend on `layer:server`".
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct BoundaryRule {
+    pub from: String,
+    /// Tags which dependencies may not have.
+    #[serde(default)]
+    pub not_depend_on: Vec<String>,
+    /// Tags of which dependencies must have at least one, when specified.
+    #[serde(default)]
+    pub only_depend_on: Option<Vec<String>>,
+}
+
+impl BoundaryRule {
+    /// Whether a package tagged `from` may depend on a package with
+    /// `dependency_tags`.
+    pub fn allows<S>(&self, dependency_tags: &[S]) -> bool
+    where
+        S: AsRef<str>,
+    {
+        let has_tag = |tags: &[String]| {
+            dependency_tags
+                .iter()
+                .any(|tag| tags.iter().any(|rule_tag| rule_tag == tag.as_ref()))
+        };
+        !has_tag(&self.not_depend_on)
+            && self
+                .only_depend_on
+                .as_ref()
+                .is_none_or(|only_depend_on| has_tag(only_depend_on))
+    }
+}
+
+impl Display for BoundaryRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut constraints = Vec::new();
+        if !self.not_depend_on.is_empty() {
+            constraints.push(format!(
+                "{} may not depend on {}",
+                self.from,
+                self.not_depend_on.join(", ")
+            ));
+        }
+        if let Some(only_depend_on) = &self.only_depend_on {
+            constraints.push(format!(
+                "{} may only depend on {}",
+                self.from,
+                only_depend_on.join(", ")
+            ));
+        }
+        write!(f, "{}", constraints.join("; "))
+    }
+}
+
+fn all_dependency_groups() -> Vec<String> {
+    DependencyGroup::VALUES
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn deserialize_dependency_groups<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let dependency_groups = Vec::<String>::deserialize(deserializer)?;
+    match dependency_groups
+        .iter()
+        .find(|group| !DependencyGroup::VALUES.contains(&group.as_str()))
+    {
+        Some(group) => Err(D::Error::unknown_variant(group, &DependencyGroup::VALUES)),
+        None => Ok(dependency_groups),
+    }
+}
+
+fn deserialize_dependency_group_modes<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<String, PinMode>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let modes = HashMap::<String, PinMode>::deserialize(deserializer)?;
+    match modes
+        .keys()
+        .find(|group| !DependencyGroup::VALUES.contains(&group.as_str()))
+    {
+        Some(group) => Err(D::Error::unknown_variant(group, &DependencyGroup::VALUES)),
+        None => Ok(modes),
+    }
+}
+
+impl MonorepoConfiguration {
+    const FILENAME: &'static str = "monorepo.json";
+
+    /// Read the configuration from the monorepo root, falling back to the
+    /// default configuration when no configuration file exists.
+    pub fn from_directory(root: &Path) -> Result<MonorepoConfiguration, FromFileError> {
+        let filename = root.join(Self::FILENAME);
+        if !filename.is_file() {
+            return Ok(MonorepoConfiguration::default());
+        }
+        read_json_from_file(&filename)
+    }
+}
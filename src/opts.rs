@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use clap::{crate_version, ArgAction, Parser, ValueEnum};
-use typescript_tools::query;
+use typescript_tools::{pin, query, semver};
 
 #[derive(Debug, Parser)]
 #[clap(name = "monorepo", version = crate_version!(), author = "Eric Crosson <eric.s.crosson@utexas.edu>")]
@@ -26,6 +26,23 @@ pub enum ClapSubCommand {
 
     #[clap(about = "Lint internal packages for consistent use of external dependency versions")]
     Lint(Lint),
+
+    #[clap(about = "Bump the versions of internal packages and pin their internal dependents")]
+    Version(Version),
+
+    #[clap(about = "Plan releases of internal packages from local git history")]
+    Release(Release),
+
+    #[clap(
+        about = "Prepend release notes from conventional commits to each package's CHANGELOG.md"
+    )]
+    Changelog(Changelog),
+
+    #[clap(about = "Record and apply intents to release internal packages")]
+    Changeset(Changeset),
+
+    #[clap(about = "Plan publication of internal packages to the registry")]
+    Publish(Publish),
 }
 
 #[derive(Debug, Parser)]
@@ -37,6 +54,10 @@ pub struct Link {
     /// Modify tsconfig.json files as necessary to restore link invariant
     #[clap(long = "write", action = ArgAction::SetTrue)]
     pub action: Action,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
 }
 
 #[derive(Debug, Parser)]
@@ -48,6 +69,190 @@ pub struct Pin {
     /// Modify package.json files as necessary to restore pin invariant
     #[clap(long = "write", action = ArgAction::SetTrue)]
     pub action: Action,
+
+    /// Convert internal dependencies to the specified protocol (requires --write)
+    #[clap(long = "protocol", value_enum, requires = "action")]
+    pub protocol: Option<SpecifierProtocol>,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: AnnotatedLintFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SpecifierProtocol {
+    Workspace,
+    Version,
+}
+
+impl From<SpecifierProtocol> for pin::SpecifierProtocol {
+    fn from(value: SpecifierProtocol) -> Self {
+        match value {
+            SpecifierProtocol::Workspace => Self::Workspace,
+            SpecifierProtocol::Version => Self::Version,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct Version {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Release type (major, minor, patch or prerelease) or explicit version
+    pub bump: String,
+
+    /// Package to bump, may be repeated (defaults to every internal package)
+    #[clap(short, long = "package")]
+    pub packages: Vec<String>,
+
+    /// Prerelease identifier, such as beta
+    #[clap(long)]
+    pub preid: Option<String>,
+
+    /// Bump internal packages which depend on a bumped package
+    #[clap(long)]
+    pub cascade: bool,
+
+    /// Print the plan without modifying package.json files
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct Release {
+    #[clap(subcommand)]
+    pub subcommand: ClapReleaseSubCommand,
+}
+
+#[derive(Debug, Parser)]
+pub enum ClapReleaseSubCommand {
+    #[clap(
+        about = "Print the next version of each internal package, inferred from conventional commits since its last tag"
+    )]
+    Plan(ReleasePlan),
+}
+
+#[derive(Debug, Parser)]
+pub struct ReleasePlan {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to print the release plan (defaults to table)
+    #[clap(long = "format", value_enum, default_value = "table")]
+    pub format: ReleasePlanFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ReleasePlanFormat {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+pub struct Changelog {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Print the new sections without modifying CHANGELOG.md files
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct Changeset {
+    #[clap(subcommand)]
+    pub subcommand: ClapChangesetSubCommand,
+}
+
+#[derive(Debug, Parser)]
+pub enum ClapChangesetSubCommand {
+    #[clap(about = "Write a changeset releasing the specified packages")]
+    Add(ChangesetAdd),
+
+    #[clap(
+        about = "Bump versions, pin internal dependents and write changelogs from every changeset"
+    )]
+    Apply(ChangesetApply),
+}
+
+#[derive(Debug, Parser)]
+pub struct ChangesetAdd {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Release type of the specified packages
+    #[clap(value_enum)]
+    pub release: ChangesetRelease,
+
+    /// Package to release, may be repeated
+    #[clap(short, long = "package", required = true)]
+    pub packages: Vec<String>,
+
+    /// Summary of the changes, for changelogs
+    #[clap(short, long)]
+    pub message: String,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ChangesetRelease {
+    Major,
+    Minor,
+    Patch,
+}
+
+impl From<ChangesetRelease> for semver::ReleaseType {
+    fn from(value: ChangesetRelease) -> Self {
+        match value {
+            ChangesetRelease::Major => Self::Major,
+            ChangesetRelease::Minor => Self::Minor,
+            ChangesetRelease::Patch => Self::Patch,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct ChangesetApply {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Print the plan without modifying any files
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct Publish {
+    #[clap(subcommand)]
+    pub subcommand: ClapPublishSubCommand,
+}
+
+#[derive(Debug, Parser)]
+pub enum ClapPublishSubCommand {
+    #[clap(
+        about = "Print the public packages whose versions are not in the registry, in dependency order"
+    )]
+    Plan(PublishPlan),
+}
+
+#[derive(Debug, Parser)]
+pub struct PublishPlan {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Registry snapshot: a directory of packuments or a JSON file of `npm view --json` output
+    #[clap(long)]
+    pub registry: PathBuf,
+
+    /// Format in which to print the publish plan (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
 }
 
 #[derive(Debug, Parser)]
@@ -81,6 +286,21 @@ pub enum ClapQuerySubCommand {
         about = "Print a JSON object mapping a package name to a list of relative paths to its internal dependencies"
     )]
     InternalDependencies(InternalDependencies),
+
+    #[clap(
+        about = "Print a JSON object mapping each internal package to the versions of an external dependency it resolves to in the lockfile"
+    )]
+    Resolved(Resolved),
+}
+
+#[derive(Debug, Parser)]
+pub struct Resolved {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// External dependency to report resolved versions of
+    pub dependency: String,
 }
 
 #[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -117,8 +337,36 @@ pub struct Lint {
 
 #[derive(Debug, Parser)]
 pub enum ClapLintSubCommand {
-    #[clap(about = "Lint the used versions of an external dependency for consistency")]
+    #[clap(about = "Lint the used versions of external dependencies for consistency")]
     DependencyVersion(DependencyVersion),
+
+    #[clap(about = "Lint TypeScript projects for overlapping inputs and outputs")]
+    Tsconfig(Tsconfig),
+
+    #[clap(about = "Lint package entry points against the output of the TypeScript compiler")]
+    EntryPoints(EntryPoints),
+
+    #[clap(about = "Lint the exports field of internal packages")]
+    Exports(Exports),
+
+    #[clap(
+        about = "Lint internal packages for unmet peer dependencies of their internal dependencies"
+    )]
+    PeerDependencies(PeerDependencies),
+
+    #[clap(about = "Lint TypeScript imports for undeclared and unused dependencies")]
+    Imports(Imports),
+
+    #[clap(
+        about = "Lint imports between internal packages for modules their exports do not expose"
+    )]
+    DeepImports(DeepImports),
+
+    #[clap(about = "Lint internal dependencies against the tag-based boundary rules")]
+    Boundaries(Boundaries),
+
+    #[clap(about = "Lint the lockfile against the dependencies of internal packages")]
+    Lockfile(Lockfile),
 }
 
 #[derive(Debug, Parser)]
@@ -127,9 +375,134 @@ pub struct DependencyVersion {
     #[clap(short, long, default_value = ".")]
     pub root: PathBuf,
 
-    /// External dependency to lint for consistency of version used
+    /// External dependency to lint for consistency of version used (defaults to all)
     #[clap(short, long = "dependency")]
     pub dependencies: Vec<String>,
+
+    /// Rewrite package.json files to use a consistent version
+    #[clap(long = "write", action = ArgAction::SetTrue)]
+    pub action: Action,
+
+    /// Version to write (defaults to most-common)
+    #[clap(long, value_enum, default_value = "most-common", requires = "action")]
+    pub strategy: DependencyVersionStrategy,
+
+    /// Explicit version to write, instead of choosing one with --strategy
+    #[clap(
+        long,
+        requires = "action",
+        requires = "dependencies",
+        conflicts_with = "strategy"
+    )]
+    pub version: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DependencyVersionStrategy {
+    /// The version used by the most packages
+    MostCommon,
+    /// The highest version in use
+    Highest,
+}
+
+#[derive(Debug, Parser)]
+pub struct Tsconfig {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub struct EntryPoints {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub struct Exports {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Require every export target to exist on disk (run after building)
+    #[clap(long)]
+    pub check_files: bool,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct PeerDependencies {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct Imports {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct DeepImports {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct Boundaries {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(Debug, Parser)]
+pub struct Lockfile {
+    /// Path to monorepo root
+    #[clap(short, long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// Format in which to report lint issues (defaults to text)
+    #[clap(long = "format", value_enum, default_value = "text")]
+    pub format: LintFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum LintFormat {
+    Text,
+    Json,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum AnnotatedLintFormat {
+    Text,
+    Json,
+    /// GitHub Actions workflow commands
+    Github,
+    /// SARIF 2.1.0 log
+    Sarif,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
@@ -1,5 +1,9 @@
 use std::fmt::Display;
+use std::path::PathBuf;
 
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
 use crate::typescript_config::{
     TypescriptConfig, TypescriptParentProjectReference, TypescriptProjectReference,
 };
@@ -16,19 +20,74 @@ pub(crate) struct OutOfDatePackageProjectReferences {
     pub desired_references: Vec<TypescriptProjectReference>,
 }
 
-#[derive(Debug)]
-pub enum MonorepoTypescriptConfig {
-    #[non_exhaustive]
-    Parent(TypescriptParentProjectReference),
-    #[non_exhaustive]
-    Package(TypescriptConfig),
+/// Difference between the project references declared in a tsconfig file and
+/// the project references required by the monorepo's internal dependency graph.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct OutOfDateTypescriptConfig {
+    /// Relative path to the tsconfig file from the monorepo root.
+    pub path: PathBuf,
+    /// Project references required by the internal dependency graph.
+    pub expected_references: Vec<String>,
+    /// Project references currently declared in the tsconfig file.
+    pub actual_references: Vec<String>,
+    /// Project references which must be added to the tsconfig file.
+    pub references_to_add: Vec<String>,
+    /// Project references which must be removed from the tsconfig file.
+    pub references_to_remove: Vec<String>,
+    /// True when the declared references are correct but not in the
+    /// expected (sorted) order.
+    pub order_only: bool,
 }
 
-#[derive(Debug)]
-pub(crate) struct OutOfDateTypescriptConfig {
-    config_file: MonorepoTypescriptConfig,
-    #[allow(dead_code)]
-    desired_references: Vec<TypescriptProjectReference>,
+impl OutOfDateTypescriptConfig {
+    pub(crate) fn new(
+        path: PathBuf,
+        actual_references: &[TypescriptProjectReference],
+        expected_references: &[TypescriptProjectReference],
+    ) -> Self {
+        let actual_references: Vec<String> = actual_references
+            .iter()
+            .map(|reference| reference.path.to_owned())
+            .collect();
+        let expected_references: Vec<String> = expected_references
+            .iter()
+            .map(|reference| reference.path.to_owned())
+            .collect();
+
+        let references_to_add = unmatched_references(&expected_references, &actual_references);
+        let references_to_remove = unmatched_references(&actual_references, &expected_references);
+        let order_only = references_to_add.is_empty() && references_to_remove.is_empty();
+
+        Self {
+            path,
+            expected_references,
+            actual_references,
+            references_to_add,
+            references_to_remove,
+            order_only,
+        }
+    }
+}
+
+/// References in `references` without a matching occurrence in `other`,
+/// comparing as multisets so that duplicated references are reported.
+fn unmatched_references(references: &[String], other: &[String]) -> Vec<String> {
+    let mut unmatched_other: Vec<&String> = other.iter().collect();
+    references
+        .iter()
+        .filter(
+            |reference| match unmatched_other.iter().position(|other| other == reference) {
+                Some(index) => {
+                    unmatched_other.swap_remove(index);
+                    false
+                }
+                None => true,
+            },
+        )
+        .cloned()
+        .collect()
 }
 
 impl Display for OutOfDateTypescriptConfig {
@@ -36,8 +95,22 @@ impl Display for OutOfDateTypescriptConfig {
         writeln!(
             f,
             "File contains out-of-date project references: {:?}",
-            self.config_file
-        )
+            self.path
+        )?;
+        for reference in self.references_to_add.iter() {
+            writeln!(f, "\tadd: {}", reference)?;
+        }
+        for reference in self.references_to_remove.iter() {
+            writeln!(f, "\tremove: {}", reference)?;
+        }
+        if self.order_only {
+            writeln!(
+                f,
+                "\treorder: expected {:?} but found {:?}",
+                self.expected_references, self.actual_references
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -48,10 +121,11 @@ impl From<OutOfDateParentProjectReferences> for OutOfDateTypescriptConfig {
             desired_references,
         }: OutOfDateParentProjectReferences,
     ) -> Self {
-        Self {
-            config_file: MonorepoTypescriptConfig::Parent(tsconfig),
-            desired_references,
-        }
+        Self::new(
+            tsconfig.path(),
+            &tsconfig.contents.references,
+            &desired_references,
+        )
     }
 }
 
@@ -62,20 +136,25 @@ impl From<OutOfDatePackageProjectReferences> for OutOfDateTypescriptConfig {
             desired_references,
         }: OutOfDatePackageProjectReferences,
     ) -> Self {
-        Self {
-            config_file: MonorepoTypescriptConfig::Package(tsconfig),
-            desired_references,
-        }
+        Self::new(
+            tsconfig.path(),
+            &tsconfig.project_references(),
+            &desired_references,
+        )
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
 #[non_exhaustive]
 pub struct AllOutOfDateTypescriptConfig(Vec<OutOfDateTypescriptConfig>);
 
 impl FromIterator<OutOfDateTypescriptConfig> for AllOutOfDateTypescriptConfig {
     fn from_iter<T: IntoIterator<Item = OutOfDateTypescriptConfig>>(iter: T) -> Self {
-        Self(iter.into_iter().collect())
+        let mut collection: Vec<_> = iter.into_iter().collect();
+        // Sort by path for deterministic output
+        collection.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        Self(collection)
     }
 }
 
@@ -83,6 +162,10 @@ impl AllOutOfDateTypescriptConfig {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &OutOfDateTypescriptConfig> {
+        self.0.iter()
+    }
 }
 
 impl Display for AllOutOfDateTypescriptConfig {
@@ -93,3 +176,75 @@ impl Display for AllOutOfDateTypescriptConfig {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{AllOutOfDateTypescriptConfig, OutOfDateTypescriptConfig};
+    use crate::typescript_config::TypescriptProjectReference;
+
+    fn references(paths: &[&str]) -> Vec<TypescriptProjectReference> {
+        paths
+            .iter()
+            .map(|path| TypescriptProjectReference {
+                path: path.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_separate_references_to_add_and_remove() {
+        let out_of_date = OutOfDateTypescriptConfig::new(
+            PathBuf::from("packages/a/tsconfig.json"),
+            &references(&["../b", "../c"]),
+            &references(&["../c", "../d"]),
+        );
+        assert_eq!(vec!["../d"], out_of_date.references_to_add);
+        assert_eq!(vec!["../b"], out_of_date.references_to_remove);
+        assert!(!out_of_date.order_only);
+    }
+
+    #[test]
+    fn should_remove_duplicated_references() {
+        let out_of_date = OutOfDateTypescriptConfig::new(
+            PathBuf::from("packages/a/tsconfig.json"),
+            &references(&["../b", "../c", "../b"]),
+            &references(&["../b", "../c"]),
+        );
+        assert!(out_of_date.references_to_add.is_empty());
+        assert_eq!(vec!["../b"], out_of_date.references_to_remove);
+        assert!(!out_of_date.order_only);
+    }
+
+    #[test]
+    fn should_display_all_data() {
+        let report: AllOutOfDateTypescriptConfig = vec![
+            OutOfDateTypescriptConfig::new(
+                PathBuf::from("packages/b/tsconfig.json"),
+                &references(&["../c", "../b"]),
+                &references(&["../b", "../c"]),
+            ),
+            OutOfDateTypescriptConfig::new(
+                PathBuf::from("packages/a/tsconfig.json"),
+                &references(&["../c"]),
+                &references(&["../b"]),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let expected = r#"
+File contains out-of-date project references: "packages/a/tsconfig.json"
+	add: ../b
+	remove: ../c
+
+File contains out-of-date project references: "packages/b/tsconfig.json"
+	reorder: expected ["../b", "../c"] but found ["../c", "../b"]
+
+"#
+        .trim_start();
+        let actual = format!("{}", report);
+        assert_eq!(expected, actual);
+    }
+}
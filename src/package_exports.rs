@@ -0,0 +1,363 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+/// Target of a subpath in a package's `exports` field.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ExportsTarget {
+    /// A path relative to the package directory, such as `./lib/index.js`.
+    Path(String),
+    /// Targets keyed by condition (`import`, `require`, `types`, ...), in
+    /// order of precedence.
+    Conditions(Vec<(String, ExportsTarget)>),
+    /// Targets to try in order.
+    Fallbacks(Vec<ExportsTarget>),
+    /// An explicitly unexported subpath.
+    Null,
+}
+
+impl ExportsTarget {
+    fn collect_paths<'a>(&'a self, location: String, paths: &mut Vec<(String, &'a str)>) {
+        match self {
+            ExportsTarget::Path(path) => paths.push((location, path)),
+            ExportsTarget::Conditions(conditions) => {
+                for (condition, target) in conditions {
+                    target.collect_paths(format!("{}[{:?}]", location, condition), paths);
+                }
+            }
+            ExportsTarget::Fallbacks(fallbacks) => {
+                for (index, target) in fallbacks.iter().enumerate() {
+                    target.collect_paths(format!("{}[{}]", location, index), paths);
+                }
+            }
+            ExportsTarget::Null => {}
+        }
+    }
+}
+
+/// The `exports` field of a package manifest, normalized to a list of
+/// subpaths (`.`, `./feature` or `./features/*`) and their targets.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PackageExports(Vec<(String, ExportsTarget)>);
+
+/// A reason an `exports` field would be rejected by Node.js. `location`
+/// names the offending value, for example `exports["./feature"]["import"]`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ExportsIssue {
+    /// An object mixes subpath keys (starting with `.`) and condition keys.
+    #[non_exhaustive]
+    MixedSubpathsAndConditions { location: String },
+    /// A subpath key contains more than one `*` wildcard.
+    #[non_exhaustive]
+    InvalidSubpath { location: String, subpath: String },
+    /// A target does not start with `./`, or escapes the package directory.
+    #[non_exhaustive]
+    InvalidTarget { location: String, target: String },
+    /// The `default` condition matches everything, so conditions after it
+    /// are never used.
+    #[non_exhaustive]
+    DefaultConditionNotLast { location: String },
+    /// A target does not exist on disk.
+    #[non_exhaustive]
+    MissingTarget { location: String, target: String },
+}
+
+impl Display for ExportsIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportsIssue::MixedSubpathsAndConditions { location } => {
+                write!(f, "{}: mixes subpath keys and condition keys", location)
+            }
+            ExportsIssue::InvalidSubpath { location, subpath } => write!(
+                f,
+                "{}: subpath {:?} contains more than one wildcard",
+                location, subpath
+            ),
+            ExportsIssue::InvalidTarget { location, target } => write!(
+                f,
+                "{}: target {:?} must be a relative path starting with \"./\" inside the package",
+                location, target
+            ),
+            ExportsIssue::DefaultConditionNotLast { location } => write!(
+                f,
+                "{}: the \"default\" condition must be the last condition",
+                location
+            ),
+            ExportsIssue::MissingTarget { location, target } => {
+                write!(f, "{}: target {:?} does not exist", location, target)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InvalidExportsError(pub Vec<ExportsIssue>);
+
+impl Display for InvalidExportsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid package exports")
+    }
+}
+
+impl std::error::Error for InvalidExportsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+fn is_valid_target(target: &str) -> bool {
+    match target.strip_prefix("./") {
+        Some(relative_target) => !relative_target.split('/').any(|segment| {
+            segment == "." || segment == ".." || segment.eq_ignore_ascii_case("node_modules")
+        }),
+        None => false,
+    }
+}
+
+fn parse_target(
+    location: String,
+    value: &serde_json::Value,
+    issues: &mut Vec<ExportsIssue>,
+) -> ExportsTarget {
+    match value {
+        serde_json::Value::String(target) => {
+            if !is_valid_target(target) {
+                issues.push(ExportsIssue::InvalidTarget {
+                    location,
+                    target: target.to_owned(),
+                });
+            }
+            ExportsTarget::Path(target.to_owned())
+        }
+        serde_json::Value::Array(fallbacks) => ExportsTarget::Fallbacks(
+            fallbacks
+                .iter()
+                .enumerate()
+                .map(|(index, fallback)| {
+                    parse_target(format!("{}[{}]", location, index), fallback, issues)
+                })
+                .collect(),
+        ),
+        serde_json::Value::Object(conditions) => {
+            if conditions.keys().any(|key| key.starts_with('.')) {
+                issues.push(ExportsIssue::MixedSubpathsAndConditions {
+                    location: location.clone(),
+                });
+            }
+            if let Some(index) = conditions.keys().position(|key| key == "default") {
+                if index != conditions.len() - 1 {
+                    issues.push(ExportsIssue::DefaultConditionNotLast {
+                        location: location.clone(),
+                    });
+                }
+            }
+            ExportsTarget::Conditions(
+                conditions
+                    .iter()
+                    .map(|(condition, target)| {
+                        let target =
+                            parse_target(format!("{}[{:?}]", location, condition), target, issues);
+                        (condition.to_owned(), target)
+                    })
+                    .collect(),
+            )
+        }
+        serde_json::Value::Null => ExportsTarget::Null,
+        serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+            issues.push(ExportsIssue::InvalidTarget {
+                location,
+                target: value.to_string(),
+            });
+            ExportsTarget::Null
+        }
+    }
+}
+
+impl PackageExports {
+    /// Parse the value of a package's `exports` field, collecting every
+    /// reason Node.js would reject it.
+    pub fn parse(exports: &serde_json::Value) -> Result<PackageExports, InvalidExportsError> {
+        let mut issues = Vec::new();
+        let location = String::from("exports");
+
+        let subpaths = match exports {
+            serde_json::Value::Object(map) if map.keys().any(|key| key.starts_with('.')) => {
+                if map.keys().any(|key| !key.starts_with('.')) {
+                    issues.push(ExportsIssue::MixedSubpathsAndConditions {
+                        location: location.clone(),
+                    });
+                }
+                map.iter()
+                    .filter(|(subpath, _)| subpath.starts_with('.'))
+                    .map(|(subpath, target)| {
+                        let location = format!("{}[{:?}]", location, subpath);
+                        let is_valid_subpath = (subpath == "." || subpath.starts_with("./"))
+                            && subpath.matches('*').count() <= 1;
+                        if !is_valid_subpath {
+                            issues.push(ExportsIssue::InvalidSubpath {
+                                location: location.clone(),
+                                subpath: subpath.to_owned(),
+                            });
+                        }
+                        (
+                            subpath.to_owned(),
+                            parse_target(location, target, &mut issues),
+                        )
+                    })
+                    .collect()
+            }
+            // Shorthand for the main export: a target, fallbacks or conditions
+            _ => vec![(
+                String::from("."),
+                parse_target(location, exports, &mut issues),
+            )],
+        };
+
+        match issues.is_empty() {
+            true => Ok(PackageExports(subpaths)),
+            false => Err(InvalidExportsError(issues)),
+        }
+    }
+
+    pub fn subpaths(&self) -> impl Iterator<Item = (&str, &ExportsTarget)> {
+        self.0
+            .iter()
+            .map(|(subpath, target)| (subpath.as_str(), target))
+    }
+
+    /// Whether a subpath, such as `.` or `./feature`, may be imported from
+    /// the package. Exact subpaths take precedence over patterns, and the
+    /// pattern with the longest prefix before its wildcard wins, as in
+    /// Node.js. Subpaths resolving to `null` are not exported.
+    pub fn is_exported(&self, subpath: &str) -> bool {
+        if let Some((_, target)) = self.0.iter().find(|(key, _)| key == subpath) {
+            return !matches!(target, ExportsTarget::Null);
+        }
+        self.0
+            .iter()
+            .filter_map(|(key, target)| {
+                let prefix_length = match key.split_once('*') {
+                    Some((prefix, suffix)) => {
+                        let matches = subpath.len() >= prefix.len() + suffix.len()
+                            && subpath.starts_with(prefix)
+                            && subpath.ends_with(suffix);
+                        matches.then_some(prefix.len())
+                    }
+                    // Deprecated folder mappings, such as `./features/`
+                    None => (key.ends_with('/') && subpath.starts_with(key.as_str()))
+                        .then_some(key.len()),
+                }?;
+                Some(((prefix_length, key.len()), target))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .is_some_and(|(_, target)| !matches!(target, ExportsTarget::Null))
+    }
+
+    /// Every path target, labelled by its location in the `exports` field.
+    pub fn target_paths(&self) -> Vec<(String, &str)> {
+        let mut paths = Vec::new();
+        for (subpath, target) in self.0.iter() {
+            target.collect_paths(format!("exports[{:?}]", subpath), &mut paths);
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ExportsIssue, ExportsTarget, PackageExports};
+
+    #[test]
+    fn should_parse_shorthand_exports() {
+        let exports = PackageExports::parse(&json!("./lib/index.js")).unwrap();
+        assert_eq!(
+            vec![(".", &ExportsTarget::Path(String::from("./lib/index.js")))],
+            exports.subpaths().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_parse_nested_conditions() {
+        let exports = PackageExports::parse(&json!({
+            ".": {
+                "node": {
+                    "import": "./lib/index.mjs",
+                    "require": "./lib/index.cjs"
+                },
+                "default": "./lib/index.js"
+            },
+            "./features/*": "./lib/features/*.js",
+            "./internal/*": null
+        }))
+        .unwrap();
+        assert_eq!(
+            vec![
+                (
+                    String::from(r#"exports["."]["node"]["import"]"#),
+                    "./lib/index.mjs"
+                ),
+                (
+                    String::from(r#"exports["."]["node"]["require"]"#),
+                    "./lib/index.cjs"
+                ),
+                (String::from(r#"exports["."]["default"]"#), "./lib/index.js"),
+                (
+                    String::from(r#"exports["./features/*"]"#),
+                    "./lib/features/*.js"
+                ),
+            ],
+            exports.target_paths()
+        );
+    }
+
+    #[test]
+    fn should_match_exported_subpaths() {
+        let exports = PackageExports::parse(&json!({
+            ".": "./lib/index.js",
+            "./features/*": "./lib/features/*.js",
+            "./features/internal/*": null,
+            "./package.json": "./package.json"
+        }))
+        .unwrap();
+        assert!(exports.is_exported("."));
+        assert!(exports.is_exported("./package.json"));
+        assert!(exports.is_exported("./features/button"));
+        assert!(!exports.is_exported("./features/internal/state"));
+        assert!(!exports.is_exported("./lib/index.js"));
+    }
+
+    #[test]
+    fn should_reject_invalid_shapes() {
+        let err = PackageExports::parse(&json!({
+            ".": {
+                "default": "./lib/index.js",
+                "import": "lib/index.mjs"
+            },
+            "require": "./lib/index.cjs",
+            "./*/*": "./lib/*.js"
+        }))
+        .unwrap_err();
+        assert_eq!(
+            vec![
+                ExportsIssue::MixedSubpathsAndConditions {
+                    location: String::from("exports")
+                },
+                ExportsIssue::DefaultConditionNotLast {
+                    location: String::from(r#"exports["."]"#)
+                },
+                ExportsIssue::InvalidTarget {
+                    location: String::from(r#"exports["."]["import"]"#),
+                    target: String::from("lib/index.mjs")
+                },
+                ExportsIssue::InvalidSubpath {
+                    location: String::from(r#"exports["./*/*"]"#),
+                    subpath: String::from("./*/*")
+                },
+            ],
+            err.0
+        );
+    }
+}
@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::configuration_file::ConfigurationFile;
 use crate::io::{read_json_from_file, FromFileError};
+use crate::semver::Range;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -33,6 +34,106 @@ impl DependencyGroup {
     ];
 }
 
+/// The value of a dependency in a package manifest, classified the way a
+/// package manager interprets it.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum DependencySpecifier {
+    /// A version range, such as `^1.2.3` or `1.2.3`.
+    Range(String),
+    /// A dist-tag, such as `latest`.
+    Tag(String),
+    /// An alias installing `package` under the dependency's name, such as
+    /// `npm:@scope/package@^1.2.3`.
+    Alias { package: String, specifier: String },
+    /// A local path, such as `file:../package` or `../package`.
+    File(String),
+    /// A symbolic link to a local path, such as `link:../package`.
+    Link(String),
+    /// A git repository, such as `github:user/repo#v1.2.3` or `user/repo`.
+    Git(String),
+    /// A tarball URL, such as `https://example.com/package.tgz`.
+    Tarball(String),
+    /// The `workspace:` protocol of pnpm and Yarn Berry, such as `workspace:^`.
+    Workspace(String),
+}
+
+impl DependencySpecifier {
+    pub fn parse(specifier: &str) -> Self {
+        let specifier = specifier.trim();
+        if let Some(range) = specifier.strip_prefix("workspace:") {
+            return Self::Workspace(range.to_owned());
+        }
+        if let Some(alias) = specifier.strip_prefix("npm:") {
+            // Skip the leading `@` of scoped package names
+            let (package, specifier) = match alias.get(1..).and_then(|name| name.find('@')) {
+                Some(index) => (&alias[..index + 1], &alias[index + 2..]),
+                None => (alias, ""),
+            };
+            return Self::Alias {
+                package: package.to_owned(),
+                specifier: specifier.to_owned(),
+            };
+        }
+        if let Some(path) = specifier.strip_prefix("file:") {
+            return Self::File(path.to_owned());
+        }
+        if let Some(path) = specifier.strip_prefix("link:") {
+            return Self::Link(path.to_owned());
+        }
+        let is_git = [
+            "git+",
+            "git://",
+            "github:",
+            "gitlab:",
+            "bitbucket:",
+            "gist:",
+        ]
+        .iter()
+        .any(|prefix| specifier.starts_with(prefix));
+        if is_git {
+            return Self::Git(specifier.to_owned());
+        }
+        if specifier.starts_with("http://") || specifier.starts_with("https://") {
+            let url = specifier.split('#').next().unwrap_or_default();
+            return match url.ends_with(".git") {
+                true => Self::Git(specifier.to_owned()),
+                false => Self::Tarball(specifier.to_owned()),
+            };
+        }
+        if ["./", "../", "/", "~/"]
+            .iter()
+            .any(|prefix| specifier.starts_with(prefix))
+        {
+            return Self::File(specifier.to_owned());
+        }
+        if Range::parse(specifier).is_ok() {
+            return Self::Range(specifier.to_owned());
+        }
+        // GitHub shorthand, such as `user/repo#v1.2.3`
+        let repository = specifier.split('#').next().unwrap_or_default();
+        if matches!(repository.split_once('/'), Some((user, repo)) if !user.is_empty() && !repo.is_empty() && !repo.contains('/') && !user.starts_with('@'))
+        {
+            return Self::Git(specifier.to_owned());
+        }
+        Self::Tag(specifier.to_owned())
+    }
+
+    /// Name of the package installed for a dependency declared with this
+    /// specifier, which differs from the dependency's name for aliases.
+    pub fn package_name<'a>(&'a self, dependency_name: &'a str) -> &'a str {
+        match self {
+            Self::Alias { package, .. } => package,
+            _ => dependency_name,
+        }
+    }
+
+    /// Whether the dependency is fetched from a source other than the
+    /// registry or the local file system.
+    pub fn is_remote(&self) -> bool {
+        matches!(self, Self::Git(_) | Self::Tarball(_))
+    }
+}
+
 impl ConfigurationFile for PackageManifest {
     type Contents = PackageManifestFile;
 
@@ -108,19 +209,42 @@ impl PackageManifest {
         &'a self,
         package_manifests_by_package_name: &'a HashMap<String, PackageManifest>,
     ) -> impl Iterator<Item = &'a PackageManifest> {
-        DependencyGroup::VALUES
+        self.internal_dependencies_in_groups_iter(
+            &DependencyGroup::VALUES,
+            package_manifests_by_package_name,
+        )
+    }
+
+    /// Internal dependencies declared in any of the specified dependency groups.
+    pub fn internal_dependencies_in_groups_iter<'a, S>(
+        &'a self,
+        dependency_groups: &'a [S],
+        package_manifests_by_package_name: &'a HashMap<String, PackageManifest>,
+    ) -> impl Iterator<Item = &'a PackageManifest>
+    where
+        S: AsRef<str>,
+    {
+        dependency_groups
             .iter()
             // only iterate over the objects corresponding to each dependency group
             .filter_map(|dependency_group| {
                 self.contents
                     .extra_fields
-                    .get(*dependency_group)?
+                    .get(dependency_group.as_ref())?
                     .as_object()
             })
-            // get all dependency names from all groups
-            .flat_map(|dependency_group_value| dependency_group_value.keys())
-            // filter out external packages
-            .filter_map(|package_name| package_manifests_by_package_name.get(package_name))
+            // get all dependencies from all groups
+            .flat_map(|dependency_group_value| dependency_group_value.iter())
+            // filter out external packages, resolving aliases
+            .filter_map(|(dependency_name, dependency_version)| {
+                let specifier = dependency_version.as_str().map(DependencySpecifier::parse);
+                match specifier {
+                    Some(specifier) if specifier.is_remote() => None,
+                    Some(specifier) => package_manifests_by_package_name
+                        .get(specifier.package_name(dependency_name)),
+                    None => package_manifests_by_package_name.get(dependency_name),
+                }
+            })
     }
 
     pub fn transitive_internal_dependency_package_names_exclusive<'a>(
@@ -172,4 +296,65 @@ impl PackageManifest {
             None => &self.contents.name,
         }
     }
+
+    /// Whether the package sets `"private": true`, preventing publication.
+    pub fn is_private(&self) -> bool {
+        self.contents
+            .extra_fields
+            .get("private")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DependencySpecifier;
+
+    #[test]
+    fn should_classify_dependency_specifiers() {
+        let cases = [
+            ("^1.2.3", DependencySpecifier::Range(String::from("^1.2.3"))),
+            ("", DependencySpecifier::Range(String::new())),
+            ("latest", DependencySpecifier::Tag(String::from("latest"))),
+            (
+                "npm:@scope/package@1.0.0",
+                DependencySpecifier::Alias {
+                    package: String::from("@scope/package"),
+                    specifier: String::from("1.0.0"),
+                },
+            ),
+            ("file:../a", DependencySpecifier::File(String::from("../a"))),
+            ("../a", DependencySpecifier::File(String::from("../a"))),
+            ("link:../a", DependencySpecifier::Link(String::from("../a"))),
+            (
+                "github:user/repo#v1.0.0",
+                DependencySpecifier::Git(String::from("github:user/repo#v1.0.0")),
+            ),
+            (
+                "user/repo",
+                DependencySpecifier::Git(String::from("user/repo")),
+            ),
+            (
+                "https://example.com/repo.git",
+                DependencySpecifier::Git(String::from("https://example.com/repo.git")),
+            ),
+            (
+                "https://example.com/package.tgz",
+                DependencySpecifier::Tarball(String::from("https://example.com/package.tgz")),
+            ),
+            (
+                "workspace:*",
+                DependencySpecifier::Workspace(String::from("*")),
+            ),
+        ];
+        for (specifier, expected) in cases {
+            assert_eq!(
+                expected,
+                DependencySpecifier::parse(specifier),
+                "{}",
+                specifier
+            );
+        }
+    }
 }
@@ -1,12 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 use std::path::Path;
 
+use serde::Deserialize;
+
 use crate::configuration_file::{ConfigurationFile, WriteError};
 use crate::io::FromFileError;
+use crate::monorepo_configuration::MonorepoConfiguration;
 use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
-use crate::package_manifest::{DependencyGroup, PackageManifest};
-use crate::unpinned_dependencies::{UnpinnedDependency, UnpinnedMonorepoDependencies};
+use crate::package_manifest::{DependencyGroup, DependencySpecifier, PackageManifest};
+use crate::semver::{split_operator, Range, Version};
+pub use crate::unpinned_dependencies::{
+    NonStringVersion, UnpinnedDependency, UnpinnedMonorepoDependencies, UnpinnedPackageDependencies,
+};
+use crate::unpinned_dependencies::{NON_STRING_VERSION_RULE, UNPINNED_DEPENDENCY_RULE};
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -17,11 +24,9 @@ pub struct PinError {
 impl Display for PinError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.kind {
-            PinErrorKind::NonStringVersionNumber {
-                package_name,
-                dependency_name,
-            } => {
-                write!(f, "unable to parse `{}` package.json: encountered non-string version for dependency `{}`", package_name, dependency_name)
+            PinErrorKind::NonStringVersionNumbers(non_string_versions) => {
+                writeln!(f, "unable to pin dependencies with non-string versions\n")?;
+                write!(f, "{}", non_string_versions)
             }
             _ => write!(f, "error pinning dependency versions"),
         }
@@ -34,10 +39,7 @@ impl std::error::Error for PinError {
             PinErrorKind::FromFile(err) => Some(err),
             PinErrorKind::EnumeratePackageManifests(err) => Some(err),
             PinErrorKind::Write(err) => Some(err),
-            PinErrorKind::NonStringVersionNumber {
-                package_name: _,
-                dependency_name: _,
-            } => None,
+            PinErrorKind::NonStringVersionNumbers(_) => None,
         }
     }
 }
@@ -80,24 +82,150 @@ pub enum PinErrorKind {
     EnumeratePackageManifests(EnumeratePackageManifestsError),
     #[non_exhaustive]
     Write(WriteError),
+    /// Dependencies declared with non-string values, which were left
+    /// untouched while every other dependency was pinned.
     #[non_exhaustive]
-    NonStringVersionNumber {
-        package_name: String,
-        dependency_name: String,
-    },
+    NonStringVersionNumbers(UnpinnedMonorepoDependencies),
 }
 
-fn needs_modification<'a, 'b>(
-    dependency_name: &'a String,
-    dependency_version: &'a String,
-    package_version_by_package_name: &'b HashMap<String, String>,
-) -> Option<&'b String> {
-    package_version_by_package_name
-        .get(dependency_name)
-        .and_then(|expected| match expected == dependency_version {
-            true => None,
-            false => Some(expected),
-        })
+/// How the declared version of an internal dependency must relate to the
+/// version of the depended-upon package.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum PinMode {
+    /// Declare exactly the local version, such as `1.3.0`.
+    #[default]
+    Exact,
+    /// Declare the local version, keeping the operator of a single caret,
+    /// tilde or exact comparator, such as `^1.3.0`. Other ranges are pinned
+    /// exactly.
+    PreserveOperator,
+    /// Declare any range including the local version. Ranges which do not
+    /// include the local version are rewritten as in `PreserveOperator` mode.
+    Satisfies,
+}
+
+/// Protocol in which to declare internal dependencies.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum SpecifierProtocol {
+    /// The `workspace:` protocol of pnpm and Yarn Berry, such as `workspace:^`.
+    Workspace,
+    /// A concrete version or range, as required to publish a package.
+    Version,
+}
+
+const WORKSPACE_PROTOCOL: &str = "workspace:";
+
+/// The version specifier an internal dependency should declare, or `None`
+/// when the declared specifier already conforms to the pin mode.
+fn expected_version_specifier(mode: PinMode, declared: &str, version: &str) -> Option<String> {
+    match declared.strip_prefix(WORKSPACE_PROTOCOL) {
+        // Resolved to the local version by the package manager
+        Some("*" | "^" | "~") => None,
+        Some(range) => expected_range(mode, range, version)
+            .map(|expected| format!("{}{}", WORKSPACE_PROTOCOL, expected)),
+        None => expected_range(mode, declared, version),
+    }
+}
+
+/// Convert a version specifier which resolves to `version` into the
+/// specified protocol.
+fn convert_protocol(specifier: &str, version: &str, protocol: SpecifierProtocol) -> String {
+    match (protocol, specifier.strip_prefix(WORKSPACE_PROTOCOL)) {
+        (SpecifierProtocol::Version, Some("*")) => version.to_owned(),
+        (SpecifierProtocol::Version, Some(operator @ ("^" | "~"))) => {
+            format!("{}{}", operator, version)
+        }
+        (SpecifierProtocol::Version, Some(range)) => range.to_owned(),
+        (SpecifierProtocol::Workspace, None) => match split_operator(specifier) {
+            ("", rest) if rest == version => format!("{}*", WORKSPACE_PROTOCOL),
+            (operator @ ("^" | "~"), rest) if rest == version => {
+                format!("{}{}", WORKSPACE_PROTOCOL, operator)
+            }
+            _ => format!("{}{}", WORKSPACE_PROTOCOL, specifier),
+        },
+        (SpecifierProtocol::Version, None) | (SpecifierProtocol::Workspace, Some(_)) => {
+            specifier.to_owned()
+        }
+    }
+}
+
+/// The specifier an internal dependency should declare, or `None` when the
+/// dependency is external or already conforms to the pin mode and protocol.
+fn pinned_dependency_specifier(
+    mode: PinMode,
+    protocol: Option<SpecifierProtocol>,
+    dependency_name: &str,
+    declared: &str,
+    package_version_by_package_name: &HashMap<String, String>,
+) -> Option<String> {
+    match DependencySpecifier::parse(declared) {
+        DependencySpecifier::Alias { package, specifier } => {
+            let version = package_version_by_package_name.get(&package)?;
+            let expected = expected_version_specifier(mode, &specifier, version)?;
+            Some(format!("npm:{}@{}", package, expected))
+        }
+        // Local paths and remote sources do not declare a version
+        DependencySpecifier::File(_)
+        | DependencySpecifier::Link(_)
+        | DependencySpecifier::Git(_)
+        | DependencySpecifier::Tarball(_) => None,
+        DependencySpecifier::Range(_)
+        | DependencySpecifier::Tag(_)
+        | DependencySpecifier::Workspace(_) => {
+            let version = package_version_by_package_name.get(dependency_name)?;
+            let pinned = expected_version_specifier(mode, declared, version)
+                .unwrap_or_else(|| declared.to_owned());
+            let expected = match protocol {
+                Some(protocol) => convert_protocol(&pinned, version, protocol),
+                None => pinned,
+            };
+            match expected == declared {
+                true => None,
+                false => Some(expected),
+            }
+        }
+    }
+}
+
+fn expected_range(mode: PinMode, declared: &str, version: &str) -> Option<String> {
+    let local_version = match Version::parse(version) {
+        Ok(local_version) => local_version,
+        // Without a semantic version only an exact pin is meaningful
+        Err(_) => {
+            return match declared == version {
+                true => None,
+                false => Some(version.to_owned()),
+            }
+        }
+    };
+    // Only a single caret, tilde or exact comparator keeps its operator;
+    // compound ranges, bounds and X-ranges fall back to an exact pin
+    let preserve_operator = || {
+        let (operator, rest) = split_operator(declared.trim());
+        let is_single_comparator = !rest.contains(|c: char| c.is_whitespace() || c == '|')
+            && rest
+                .split(['.', '-', '+'])
+                .take(3)
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+        match operator {
+            "^" | "~" | "=" if is_single_comparator => format!("{}{}", operator, version),
+            _ => version.to_owned(),
+        }
+    };
+
+    let expected = match mode {
+        PinMode::Exact => version.to_owned(),
+        PinMode::PreserveOperator => preserve_operator(),
+        PinMode::Satisfies => match Range::parse(declared) {
+            Ok(range) if range.satisfies(&local_version) => return None,
+            _ => preserve_operator(),
+        },
+    };
+    match expected == declared {
+        true => None,
+        false => Some(expected),
+    }
 }
 
 fn get_dependency_group_mut<'a>(
@@ -115,11 +243,53 @@ pub fn modify<P>(root: P) -> Result<(), PinError>
 where
     P: AsRef<Path>,
 {
-    let root = root.as_ref();
+    modify_internal(root.as_ref(), None)
+}
+
+/// Pin internal dependencies like `modify`, additionally converting every
+/// internal dependency into the specified protocol. Converting to
+/// `SpecifierProtocol::Version` prepares packages for publishing.
+pub fn convert<P>(root: P, protocol: SpecifierProtocol) -> Result<(), PinError>
+where
+    P: AsRef<Path>,
+{
+    modify_internal(root.as_ref(), Some(protocol))
+}
+
+fn modify_internal(root: &Path, protocol: Option<SpecifierProtocol>) -> Result<(), PinError> {
     let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let configuration = MonorepoConfiguration::from_directory(root)?;
 
-    let package_manifest_by_package_name = lerna_manifest.package_manifests_by_package_name()?;
+    let mut package_manifest_by_package_name =
+        lerna_manifest.package_manifests_by_package_name()?;
+    let (modified_package_names, non_string_versions) = pin_package_manifests(
+        root,
+        &configuration,
+        &mut package_manifest_by_package_name,
+        protocol,
+    )?;
+
+    for package_manifest in package_manifest_by_package_name.into_values() {
+        if modified_package_names.contains(&package_manifest.contents.name) {
+            PackageManifest::write(root, package_manifest)?
+        }
+    }
 
+    match non_string_versions.is_empty() {
+        true => Ok(()),
+        false => Err(PinErrorKind::NonStringVersionNumbers(non_string_versions))?,
+    }
+}
+
+/// Pin the internal dependencies of every package manifest in memory,
+/// returning the names of the modified packages and the dependencies with
+/// non-string versions, which are left untouched.
+pub(crate) fn pin_package_manifests(
+    root: &Path,
+    configuration: &MonorepoConfiguration,
+    package_manifest_by_package_name: &mut HashMap<String, PackageManifest>,
+    protocol: Option<SpecifierProtocol>,
+) -> Result<(HashSet<String>, UnpinnedMonorepoDependencies), PinError> {
     let package_version_by_package_name: HashMap<String, String> = package_manifest_by_package_name
         .values()
         .map(|package| {
@@ -130,44 +300,60 @@ where
         })
         .collect();
 
-    for (package_name, mut package_manifest) in package_manifest_by_package_name {
+    let mut modified_package_names = HashSet::new();
+    let mut non_string_versions = Vec::new();
+    for package_manifest in package_manifest_by_package_name.values_mut() {
         let mut dirty = false;
+        let mut package_non_string_versions = Vec::new();
         for dependency_group in DependencyGroup::VALUES {
-            let dependencies = get_dependency_group_mut(&mut package_manifest, dependency_group);
+            let mode = configuration.pin.mode(dependency_group);
+            let dependencies = get_dependency_group_mut(package_manifest, dependency_group);
             if dependencies.is_none() {
                 continue;
             }
             let dependencies = dependencies.unwrap();
 
-            dependencies
-                .into_iter()
-                .try_for_each(
-                    |(dependency_name, dependency_version)| match &dependency_version {
-                        serde_json::Value::String(dep_version) => {
-                            if let Some(expected) = needs_modification(
-                                dependency_name,
-                                dep_version,
-                                &package_version_by_package_name,
-                            ) {
-                                *dependency_version = expected.to_owned().into();
-                                dirty = true;
-                            }
-                            Ok(())
+            for (dependency_name, dependency_version) in dependencies.iter_mut() {
+                match &dependency_version {
+                    serde_json::Value::String(dep_version) => {
+                        if let Some(expected) = pinned_dependency_specifier(
+                            mode,
+                            protocol,
+                            dependency_name,
+                            dep_version,
+                            &package_version_by_package_name,
+                        ) {
+                            *dependency_version = expected.into();
+                            dirty = true;
                         }
-                        _ => Err(PinErrorKind::NonStringVersionNumber {
-                            package_name: package_name.clone(),
-                            dependency_name: dependency_name.to_owned(),
-                        }),
-                    },
-                )?;
+                    }
+                    _ => package_non_string_versions.push(NonStringVersion {
+                        name: dependency_name.to_owned(),
+                        group: dependency_group.to_owned(),
+                        actual: dependency_version.to_owned(),
+                        line: None,
+                    }),
+                }
+            }
         }
 
+        let mut package = UnpinnedPackageDependencies::new(
+            package_manifest.path(),
+            Vec::new(),
+            package_non_string_versions,
+        );
+        package.locate(root)?;
+        non_string_versions.push(package);
+
         if dirty {
-            PackageManifest::write(root, package_manifest)?
+            modified_package_names.insert(package_manifest.contents.name.to_owned());
         }
     }
 
-    Ok(())
+    Ok((
+        modified_package_names,
+        non_string_versions.into_iter().collect(),
+    ))
 }
 
 #[derive(Debug)]
@@ -179,15 +365,15 @@ pub struct PinLintError {
 impl Display for PinLintError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match &self.kind {
-            PinLintErrorKind::NonStringVersionNumber {
-                package_name,
-                dependency_name,
-            } => {
-                write!(f, "unable to parse `{}` package.json: encountered non-string version for dependency `{}`", package_name, dependency_name)
-            }
             PinLintErrorKind::UnpinnedDependencies(unpinned_dependencies) => {
                 writeln!(f, "found unpinned dependency versions\n")?;
-                write!(f, "{}", unpinned_dependencies)
+                writeln!(f, "{}", unpinned_dependencies)?;
+                write!(
+                    f,
+                    "{} unpinned dependencies in {} packages",
+                    unpinned_dependencies.dependency_count(),
+                    unpinned_dependencies.iter().count()
+                )
             }
             _ => write!(f, "error linting internal dependency versions"),
         }
@@ -199,10 +385,6 @@ impl std::error::Error for PinLintError {
         match &self.kind {
             PinLintErrorKind::FromFile(err) => Some(err),
             PinLintErrorKind::EnumeratePackageManifests(err) => Some(err),
-            PinLintErrorKind::NonStringVersionNumber {
-                package_name: _,
-                dependency_name: _,
-            } => None,
             PinLintErrorKind::UnpinnedDependencies(_) => None,
         }
     }
@@ -237,37 +419,30 @@ pub enum PinLintErrorKind {
     #[non_exhaustive]
     EnumeratePackageManifests(EnumeratePackageManifestsError),
     #[non_exhaustive]
-    NonStringVersionNumber {
-        package_name: String,
-        dependency_name: String,
-    },
-    #[non_exhaustive]
     UnpinnedDependencies(UnpinnedMonorepoDependencies),
 }
 
-fn get_unpinned_dependency(
-    dependency_name: &String,
-    dependency_version: &String,
-    package_version_by_package_name: &HashMap<String, String>,
-) -> Option<UnpinnedDependency> {
-    package_version_by_package_name
-        .get(dependency_name)
-        .and_then(|expected| match expected == dependency_version {
-            true => None,
-            false => Some(UnpinnedDependency {
-                name: dependency_name.to_owned(),
-                actual: dependency_version.to_owned(),
-                expected: expected.to_owned(),
-            }),
-        })
-}
+/// Rules checked by `lint`, with a short description, for SARIF reports.
+pub const LINT_RULES: &[(&str, &str)] = &[
+    (
+        UNPINNED_DEPENDENCY_RULE,
+        "Internal dependency does not match the local package version",
+    ),
+    (
+        NON_STRING_VERSION_RULE,
+        "Dependency version is not a string",
+    ),
+];
 
-pub fn lint<P>(root: P) -> Result<(), PinLintError>
+/// Enumerate internal dependencies which do not conform to the configured
+/// pin modes.
+pub fn lint_report<P>(root: P) -> Result<UnpinnedMonorepoDependencies, PinLintError>
 where
     P: AsRef<Path>,
 {
     let root = root.as_ref();
     let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let configuration = MonorepoConfiguration::from_directory(root)?;
 
     let package_manifest_by_package_name = lerna_manifest.package_manifests_by_package_name()?;
 
@@ -282,30 +457,68 @@ where
         .collect();
 
     let unpinned_dependencies: UnpinnedMonorepoDependencies = package_manifest_by_package_name
-        .into_iter()
-        .map(|(package_name, package_manifest)| {
-            let unpinned_deps = package_manifest
-                .dependencies_iter()
-                .filter_map(|(dependency_name, dependency_version)| -> Option<Result<UnpinnedDependency, PinLintErrorKind>> {
+        .into_values()
+        .map(|package_manifest| {
+            let mut unpinned_deps = Vec::new();
+            let mut non_string_versions = Vec::new();
+            for dependency_group in DependencyGroup::VALUES {
+                let dependencies = match package_manifest
+                    .contents
+                    .extra_fields
+                    .get(dependency_group)
+                    .and_then(serde_json::Value::as_object)
+                {
+                    Some(dependencies) => dependencies,
+                    None => continue,
+                };
+                for (dependency_name, dependency_version) in dependencies {
                     match dependency_version {
                         serde_json::Value::String(dep_version) => {
-                            get_unpinned_dependency(
+                            if let Some(expected) = pinned_dependency_specifier(
+                                configuration.pin.mode(dependency_group),
+                                None,
                                 dependency_name,
                                 dep_version,
                                 &package_version_by_package_name,
-                            ).map(Ok)
+                            ) {
+                                unpinned_deps.push(UnpinnedDependency {
+                                    name: dependency_name.to_owned(),
+                                    group: dependency_group.to_owned(),
+                                    actual: dep_version.to_owned(),
+                                    expected,
+                                    line: None,
+                                });
+                            }
                         }
-                        _ => Some(Err(PinLintErrorKind::NonStringVersionNumber {
-                            package_name: package_name.clone(),
-                            dependency_name: dependency_name.to_owned(),
-                        })),
+                        _ => non_string_versions.push(NonStringVersion {
+                            name: dependency_name.to_owned(),
+                            group: dependency_group.to_owned(),
+                            actual: dependency_version.to_owned(),
+                            line: None,
+                        }),
                     }
-                })
-                .collect::<Result<_, _>>()?;
-            Ok((package_manifest.path(), unpinned_deps))
+                }
+            }
+
+            let mut package = UnpinnedPackageDependencies::new(
+                package_manifest.path(),
+                unpinned_deps,
+                non_string_versions,
+            );
+            // Locate the offending lines for CI annotations
+            package.locate(root)?;
+            Ok(package)
         })
-        .collect::<Result<_, PinLintErrorKind>>()?;
+        .collect::<Result<_, FromFileError>>()?;
+
+    Ok(unpinned_dependencies)
+}
 
+pub fn lint<P>(root: P) -> Result<(), PinLintError>
+where
+    P: AsRef<Path>,
+{
+    let unpinned_dependencies = lint_report(root)?;
     match unpinned_dependencies.is_empty() {
         true => Ok(()),
         false => Err(PinLintErrorKind::UnpinnedDependencies(
@@ -313,3 +526,151 @@ where
         ))?,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{
+        convert_protocol, expected_version_specifier, pinned_dependency_specifier, PinMode,
+        SpecifierProtocol,
+    };
+
+    #[test]
+    fn should_pin_exact_versions() {
+        assert_eq!(
+            Some(String::from("1.3.0")),
+            expected_version_specifier(PinMode::Exact, "^1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::Exact, "1.3.0", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn should_preserve_range_operators() {
+        assert_eq!(
+            Some(String::from("^1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, "^1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("~1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, "~1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::PreserveOperator, "^1.3.0", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn should_pin_compound_ranges_exactly() {
+        assert_eq!(
+            Some(String::from("1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, ">=1.0.0 <2", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, "1.x", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, "^1 || ^2", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("1.3.0")),
+            expected_version_specifier(PinMode::PreserveOperator, ">=1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::Satisfies, ">=1.0.0 <2", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("2.0.0")),
+            expected_version_specifier(PinMode::Satisfies, ">=1.0.0 <2", "2.0.0")
+        );
+        assert_eq!(
+            Some(String::from("2.0.0")),
+            expected_version_specifier(PinMode::Satisfies, "^1 || ~1.5", "2.0.0")
+        );
+        assert_eq!(
+            Some(String::from("~2.0.0")),
+            expected_version_specifier(PinMode::Satisfies, "~1", "2.0.0")
+        );
+    }
+
+    #[test]
+    fn should_accept_satisfying_ranges() {
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::Satisfies, "^1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("~1.3.0")),
+            expected_version_specifier(PinMode::Satisfies, "~1.2.0", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn should_validate_workspace_protocol() {
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::Exact, "workspace:^", "1.3.0")
+        );
+        assert_eq!(
+            Some(String::from("workspace:1.3.0")),
+            expected_version_specifier(PinMode::Exact, "workspace:1.2.0", "1.3.0")
+        );
+        assert_eq!(
+            None,
+            expected_version_specifier(PinMode::Satisfies, "workspace:^1.2.0", "1.3.0")
+        );
+    }
+
+    #[test]
+    fn should_convert_between_protocols() {
+        assert_eq!(
+            "^1.3.0",
+            convert_protocol("workspace:^", "1.3.0", SpecifierProtocol::Version)
+        );
+        assert_eq!(
+            "1.3.0",
+            convert_protocol("workspace:*", "1.3.0", SpecifierProtocol::Version)
+        );
+        assert_eq!(
+            "workspace:~",
+            convert_protocol("~1.3.0", "1.3.0", SpecifierProtocol::Workspace)
+        );
+        assert_eq!(
+            "workspace:^1.2.0",
+            convert_protocol("^1.2.0", "1.3.0", SpecifierProtocol::Workspace)
+        );
+    }
+
+    #[test]
+    fn should_pin_aliased_dependencies() {
+        let package_version_by_package_name =
+            HashMap::from([(String::from("@scope/a"), String::from("1.3.0"))]);
+        assert_eq!(
+            Some(String::from("npm:@scope/a@1.3.0")),
+            pinned_dependency_specifier(
+                PinMode::Exact,
+                None,
+                "a-alias",
+                "npm:@scope/a@1.2.0",
+                &package_version_by_package_name
+            )
+        );
+        assert_eq!(
+            None,
+            pinned_dependency_specifier(
+                PinMode::Exact,
+                None,
+                "@scope/a",
+                "file:../a",
+                &package_version_by_package_name
+            )
+        );
+    }
+}
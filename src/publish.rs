@@ -0,0 +1,384 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::io::{read_json_from_file, FromFileError};
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct PublishError {
+    pub kind: PublishErrorKind,
+}
+
+impl Display for PublishError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            PublishErrorKind::ReadRegistry { path, .. } => {
+                write!(f, "unable to read registry snapshot {:?}", path)
+            }
+            _ => write!(f, "error planning publication"),
+        }
+    }
+}
+
+impl std::error::Error for PublishError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            PublishErrorKind::FromFile(err) => Some(err),
+            PublishErrorKind::EnumeratePackageManifests(err) => Some(err),
+            PublishErrorKind::ReadRegistry { err, .. } => Some(err),
+        }
+    }
+}
+
+impl From<FromFileError> for PublishError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: PublishErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for PublishError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: PublishErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PublishErrorKind {
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    ReadRegistry { path: PathBuf, err: io::Error },
+}
+
+/// Versions published to the registry, read from local files instead of
+/// the network.
+#[derive(Clone, Debug, Default)]
+pub struct RegistrySnapshot {
+    versions_by_package_name: HashMap<String, BTreeSet<String>>,
+}
+
+/// Published versions from a packument (`versions` is an object keyed by
+/// version), `npm view --json` output (`versions` is a list, or a string for
+/// a single version), or a plain list of versions.
+fn published_versions(value: &serde_json::Value) -> BTreeSet<String> {
+    let strings = |values: &Vec<serde_json::Value>| {
+        values
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .map(ToOwned::to_owned)
+            .collect()
+    };
+    match value {
+        serde_json::Value::Array(versions) => strings(versions),
+        serde_json::Value::String(version) => BTreeSet::from([version.to_owned()]),
+        serde_json::Value::Object(object) => match object.get("versions") {
+            Some(serde_json::Value::Object(versions)) => versions.keys().cloned().collect(),
+            Some(versions) => published_versions(versions),
+            None => object
+                .get("version")
+                .map(published_versions)
+                .unwrap_or_default(),
+        },
+        _ => BTreeSet::new(),
+    }
+}
+
+impl RegistrySnapshot {
+    /// Read a registry snapshot from either a directory of packuments, one
+    /// JSON file per package named `<name>.json` (scoped packages may be
+    /// nested in a scope directory or use `%2f` for the slash), or a single
+    /// JSON file holding `npm view --json` output for one package or an
+    /// object mapping each package name to its packument, `npm view` output
+    /// or list of versions.
+    pub fn from_path<P>(path: P) -> Result<Self, PublishError>
+    where
+        P: AsRef<Path>,
+    {
+        fn inner(path: &Path) -> Result<RegistrySnapshot, PublishError> {
+            let mut snapshot = RegistrySnapshot::default();
+            match path.is_dir() {
+                true => snapshot.read_directory(path, path)?,
+                false => {
+                    let value: serde_json::Value = read_json_from_file(path)?;
+                    match value.get("name").and_then(serde_json::Value::as_str) {
+                        Some(package_name) => snapshot.insert(package_name, &value),
+                        None => {
+                            for (package_name, value) in value.as_object().into_iter().flatten() {
+                                snapshot.insert(package_name, value);
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(snapshot)
+        }
+        inner(path.as_ref())
+    }
+
+    fn read_directory(&mut self, root: &Path, directory: &Path) -> Result<(), PublishError> {
+        let read_registry_error = |err| PublishError {
+            kind: PublishErrorKind::ReadRegistry {
+                path: directory.to_owned(),
+                err,
+            },
+        };
+        for entry in fs::read_dir(directory).map_err(read_registry_error)? {
+            let path = entry.map_err(read_registry_error)?.path();
+            if path.is_dir() {
+                self.read_directory(root, &path)?;
+                continue;
+            }
+            if path.extension().is_none_or(|extension| extension != "json") {
+                continue;
+            }
+            let value: serde_json::Value = read_json_from_file(&path)?;
+            let package_name = match value.get("name").and_then(serde_json::Value::as_str) {
+                Some(package_name) => package_name.to_owned(),
+                None => path
+                    .strip_prefix(root)
+                    .expect("Packument is not in the registry snapshot")
+                    .with_extension("")
+                    .to_string_lossy()
+                    .replace('\\', "/")
+                    .replace("%2f", "/")
+                    .replace("%2F", "/"),
+            };
+            self.insert(&package_name, &value);
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, package_name: &str, value: &serde_json::Value) {
+        self.versions_by_package_name
+            .entry(package_name.to_owned())
+            .or_default()
+            .extend(published_versions(value));
+    }
+
+    pub fn is_published(&self, package_name: &str, version: &str) -> bool {
+        self.versions_by_package_name
+            .get(package_name)
+            .is_some_and(|versions| versions.contains(version))
+    }
+}
+
+/// A package whose version is not in the registry.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Publication {
+    pub name: String,
+    pub version: String,
+    pub directory: PathBuf,
+}
+
+impl Display for Publication {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}@{} ({})",
+            self.name,
+            self.version,
+            self.directory.display()
+        )
+    }
+}
+
+/// A public package which cannot be installed from the registry because it
+/// depends on a private internal package.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PrivateDependency {
+    pub name: String,
+    pub dependency: String,
+}
+
+impl Display for PrivateDependency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} depends on private package {}",
+            self.name, self.dependency
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PublishPlan {
+    /// Packages to publish, each after its internal dependencies.
+    pub packages: Vec<Publication>,
+    pub private_dependencies: Vec<PrivateDependency>,
+}
+
+impl Display for PublishPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Packages to publish:")?;
+        for package in self.packages.iter() {
+            writeln!(f, "\t{}", package)?;
+        }
+        if !self.private_dependencies.is_empty() {
+            writeln!(f, "Public packages depending on private packages:")?;
+            for private_dependency in self.private_dependencies.iter() {
+                writeln!(f, "\t{}", private_dependency)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Dependency groups a package manager installs for consumers of a package.
+const PUBLISHED_DEPENDENCY_GROUPS: [&str; 3] =
+    ["dependencies", "optionalDependencies", "peerDependencies"];
+
+/// Order internal packages so each follows its internal dependencies,
+/// breaking ties by name. Packages in a dependency cycle follow the rest,
+/// sorted by name.
+fn topological_order(
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Vec<&str> {
+    let mut dependencies: HashMap<&str, BTreeSet<&str>> = package_manifests_by_package_name
+        .iter()
+        .map(|(package_name, package_manifest)| {
+            let dependencies = package_manifest
+                .internal_dependencies_iter(package_manifests_by_package_name)
+                .map(|dependency| dependency.contents.name.as_str())
+                .filter(|dependency| dependency != package_name)
+                .collect();
+            (package_name.as_str(), dependencies)
+        })
+        .collect();
+
+    let mut order = Vec::with_capacity(dependencies.len());
+    loop {
+        let ready: BTreeSet<&str> = dependencies
+            .iter()
+            .filter(|(_, dependencies)| dependencies.is_empty())
+            .map(|(package_name, _)| *package_name)
+            .collect();
+        // Publish one package at a time so ties are broken by name
+        let next = match ready.first() {
+            Some(next) => *next,
+            None => break,
+        };
+        dependencies.remove(next);
+        for remaining in dependencies.values_mut() {
+            remaining.remove(next);
+        }
+        order.push(next);
+    }
+
+    let mut cyclic: Vec<&str> = dependencies.into_keys().collect();
+    cyclic.sort_unstable();
+    order.extend(cyclic);
+    order
+}
+
+/// List the public internal packages whose versions are not in the registry
+/// snapshot, in an order in which they can be published, and the private
+/// internal packages that public packages depend on.
+pub fn plan<P, Q>(root: P, registry: Q) -> Result<PublishPlan, PublishError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    fn inner(root: &Path, registry: &Path) -> Result<PublishPlan, PublishError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        let registry = RegistrySnapshot::from_path(registry)?;
+
+        let packages = topological_order(&package_manifests_by_package_name)
+            .into_iter()
+            .map(|package_name| &package_manifests_by_package_name[package_name])
+            .filter(|package_manifest| {
+                !package_manifest.is_private()
+                    && !registry.is_published(
+                        &package_manifest.contents.name,
+                        &package_manifest.contents.version,
+                    )
+            })
+            .map(|package_manifest| Publication {
+                name: package_manifest.contents.name.to_owned(),
+                version: package_manifest.contents.version.to_owned(),
+                directory: package_manifest.directory(),
+            })
+            .collect();
+
+        let mut private_dependencies: Vec<PrivateDependency> = package_manifests_by_package_name
+            .values()
+            .filter(|package_manifest| !package_manifest.is_private())
+            .flat_map(|package_manifest| {
+                package_manifest
+                    .internal_dependencies_in_groups_iter(
+                        &PUBLISHED_DEPENDENCY_GROUPS,
+                        &package_manifests_by_package_name,
+                    )
+                    .filter(|dependency| dependency.is_private())
+                    .map(|dependency| PrivateDependency {
+                        name: package_manifest.contents.name.to_owned(),
+                        dependency: dependency.contents.name.to_owned(),
+                    })
+            })
+            .collect();
+        private_dependencies
+            .sort_unstable_by(|a, b| (&a.name, &a.dependency).cmp(&(&b.name, &b.dependency)));
+        private_dependencies.dedup();
+
+        Ok(PublishPlan {
+            packages,
+            private_dependencies,
+        })
+    }
+    inner(root.as_ref(), registry.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::published_versions;
+
+    #[test]
+    fn should_read_published_versions() {
+        let versions = |value: serde_json::Value| -> Vec<String> {
+            published_versions(&value).into_iter().collect()
+        };
+        assert_eq!(
+            vec!["1.0.0", "1.1.0"],
+            versions(serde_json::json!({
+                "name": "a",
+                "versions": { "1.0.0": {}, "1.1.0": {} },
+            }))
+        );
+        assert_eq!(
+            vec!["1.0.0", "2.0.0"],
+            versions(serde_json::json!({ "name": "a", "versions": ["2.0.0", "1.0.0"] }))
+        );
+        assert_eq!(
+            vec!["1.0.0"],
+            versions(serde_json::json!({ "name": "a", "versions": "1.0.0" }))
+        );
+        assert_eq!(vec!["3.0.0"], versions(serde_json::json!(["3.0.0"])));
+        assert_eq!(
+            BTreeSet::<String>::new(),
+            published_versions(&serde_json::json!(null))
+        );
+    }
+}
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 
+use serde::Serialize;
+
 use crate::configuration_file::ConfigurationFile;
 use crate::io::FromFileError;
+use crate::lockfile::{Lockfile, LockfileError, Resolution};
 use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::semver::Version;
 
 #[derive(Debug)]
 #[non_exhaustive]
@@ -28,6 +32,7 @@ impl std::error::Error for QueryError {
         match &self.kind {
             QueryErrorKind::FromFile(err) => Some(err),
             QueryErrorKind::EnumeratePackageManifests(err) => Some(err),
+            QueryErrorKind::Lockfile(err) => Some(err),
             QueryErrorKind::PathInvalidUtf8(_) => None,
         }
     }
@@ -49,6 +54,14 @@ impl From<EnumeratePackageManifestsError> for QueryError {
     }
 }
 
+impl From<LockfileError> for QueryError {
+    fn from(err: LockfileError) -> Self {
+        Self {
+            kind: QueryErrorKind::Lockfile(err),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum QueryErrorKind {
     #[non_exhaustive]
@@ -56,6 +69,8 @@ pub enum QueryErrorKind {
     #[non_exhaustive]
     EnumeratePackageManifests(EnumeratePackageManifestsError),
     #[non_exhaustive]
+    Lockfile(LockfileError),
+    #[non_exhaustive]
     PathInvalidUtf8(PathBuf),
 }
 
@@ -100,7 +115,6 @@ where
                         .transitive_internal_dependency_package_names_exclusive(
                             &package_manifest_by_package_name,
                         )
-                        .into_iter()
                         .map(|dependency| match format {
                             InternalDependenciesFormat::Name => {
                                 Ok(dependency.contents.name.to_owned())
@@ -124,3 +138,67 @@ where
     }
     inner(root.as_ref(), format)
 }
+
+/// Versions of an external dependency installed for an internal package.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ResolvedDependency {
+    /// Version installed as a direct dependency, when the package declares
+    /// the dependency.
+    pub direct: Option<String>,
+    /// Every version installed, directly or through other external
+    /// dependencies, in ascending order.
+    pub versions: Vec<String>,
+}
+
+/// Report, for each internal package, the versions of `dependency` its
+/// dependencies resolve to in the lockfile. Packages which do not depend on
+/// `dependency`, directly or transitively, are omitted.
+pub fn query_resolved<P, S>(
+    root: P,
+    dependency: S,
+) -> Result<BTreeMap<String, ResolvedDependency>, QueryError>
+where
+    P: AsRef<Path>,
+    S: AsRef<str>,
+{
+    fn inner(
+        root: &Path,
+        dependency: &str,
+    ) -> Result<BTreeMap<String, ResolvedDependency>, QueryError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        let lockfile = Lockfile::from_directory(root)?;
+
+        let mut resolved_by_package = BTreeMap::new();
+        for (package_name, package_manifest) in package_manifests_by_package_name.iter() {
+            let mut versions: Vec<String> = lockfile
+                .resolved_versions(package_manifest, dependency)
+                .into_iter()
+                .collect();
+            if versions.is_empty() {
+                continue;
+            }
+            // Sort by semantic version, so that 10.0.0 follows 9.0.0
+            versions.sort_by_cached_key(|version| Version::parse(version).ok());
+            let direct = package_manifest
+                .get_dependency_version(dependency)
+                .and_then(|specifier| {
+                    lockfile.resolve(&package_manifest.directory(), dependency, &specifier)
+                })
+                .and_then(|resolution| match resolution {
+                    Resolution::Version(version) => Some(version),
+                    Resolution::Link => None,
+                });
+            resolved_by_package.insert(
+                package_name.to_owned(),
+                ResolvedDependency { direct, versions },
+            );
+        }
+
+        Ok(resolved_by_package)
+    }
+    inner(root.as_ref(), dependency.as_ref())
+}
@@ -0,0 +1,382 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::ConfigurationFile;
+use crate::conventional_commit::ConventionalCommit;
+use crate::git::{self, Commit, GitError};
+use crate::io::FromFileError;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+use crate::semver::{ReleaseType, SemverParseError, Version};
+use crate::version::{cascade_releases, internal_dependents, release_precedence};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ReleaseError {
+    pub kind: ReleaseErrorKind,
+}
+
+impl Display for ReleaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ReleaseErrorKind::InvalidVersion(package_name, _) => {
+                write!(f, "package {} has an invalid version", package_name)
+            }
+            _ => write!(f, "error planning release"),
+        }
+    }
+}
+
+impl std::error::Error for ReleaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ReleaseErrorKind::FromFile(err) => Some(err),
+            ReleaseErrorKind::EnumeratePackageManifests(err) => Some(err),
+            ReleaseErrorKind::Git(err) => Some(err),
+            ReleaseErrorKind::InvalidVersion(_, err) => Some(err),
+        }
+    }
+}
+
+impl From<FromFileError> for ReleaseError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: ReleaseErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for ReleaseError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: ReleaseErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<GitError> for ReleaseError {
+    fn from(err: GitError) -> Self {
+        Self {
+            kind: ReleaseErrorKind::Git(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ReleaseErrorKind {
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    Git(GitError),
+    #[non_exhaustive]
+    InvalidVersion(String, SemverParseError),
+}
+
+/// The commits changing an internal package since its last release.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PackageCommits {
+    /// The most recent `<name>@<version>` tag reachable from `HEAD`.
+    pub last_tag: Option<String>,
+    /// Commits changing files in the package directory, newest first.
+    pub commits: Vec<Commit>,
+}
+
+/// The most recent `<name>@<version>` tag of each package, by version.
+fn last_tags(tags: &[String]) -> HashMap<&str, (Version, &str)> {
+    let mut last_tags: HashMap<&str, (Version, &str)> = HashMap::new();
+    for tag in tags {
+        let (package_name, version) = match tag.rsplit_once('@') {
+            Some((package_name, version)) if !package_name.is_empty() => (package_name, version),
+            _ => continue,
+        };
+        let version = match Version::parse(version) {
+            Ok(version) => version,
+            Err(_) => continue,
+        };
+        match last_tags.get(package_name) {
+            Some((last_version, _)) if last_version >= &version => {}
+            _ => {
+                last_tags.insert(package_name, (version, tag));
+            }
+        }
+    }
+    last_tags
+}
+
+/// The package owning a file: the package whose directory is the deepest
+/// ancestor of the file, so that nested packages own their own files.
+fn owning_package<'a>(directories: &[(PathBuf, &'a str)], file: &Path) -> Option<&'a str> {
+    directories
+        .iter()
+        .filter(|(directory, _)| file.starts_with(directory))
+        .max_by_key(|(directory, _)| directory.components().count())
+        .map(|(_, package_name)| *package_name)
+}
+
+/// Read the commits changing each internal package since its last tag from
+/// the local git repository. A commit belongs to a package when it changes
+/// a file under the package's directory, outside any nested package.
+pub(crate) fn package_commits(
+    root: &Path,
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> Result<HashMap<String, PackageCommits>, ReleaseError> {
+    let tags = git::tags(root)?;
+    let last_tags = last_tags(&tags);
+    let directories: Vec<(PathBuf, &str)> = package_manifests_by_package_name
+        .values()
+        .map(|package_manifest| {
+            (
+                package_manifest.directory(),
+                package_manifest.contents.name.as_str(),
+            )
+        })
+        .collect();
+
+    let mut package_commits = HashMap::new();
+    for (package_name, package_manifest) in package_manifests_by_package_name {
+        let last_tag = last_tags.get(package_name.as_str()).map(|(_, tag)| *tag);
+        let commits = git::commits(root, last_tag, &package_manifest.directory())?
+            .into_iter()
+            .filter(|commit| {
+                commit
+                    .files
+                    .iter()
+                    .any(|file| owning_package(&directories, file) == Some(package_name.as_str()))
+            })
+            .collect();
+        package_commits.insert(
+            package_name.to_owned(),
+            PackageCommits {
+                last_tag: last_tag.map(ToOwned::to_owned),
+                commits,
+            },
+        );
+    }
+    Ok(package_commits)
+}
+
+/// A commit contributing to a package release.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ReleaseCommit {
+    pub hash: String,
+    pub subject: String,
+    /// The release the commit calls for, if any.
+    pub release: Option<ReleaseType>,
+}
+
+/// The planned release of an internal package.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct PackageRelease {
+    pub name: String,
+    pub current: String,
+    pub next: String,
+    pub release: ReleaseType,
+    pub last_tag: Option<String>,
+    pub commits: Vec<ReleaseCommit>,
+    /// The internal dependency whose release cascaded to this package, when
+    /// no commit to the package calls for a release of its own.
+    pub cascaded_from: Option<String>,
+}
+
+impl PackageRelease {
+    fn reason(&self) -> String {
+        match &self.cascaded_from {
+            Some(dependency) => format!("depends on {}", dependency),
+            None => {
+                let count = self
+                    .commits
+                    .iter()
+                    .filter(|commit| commit.release.is_some())
+                    .count();
+                format!("{} commit{}", count, if count == 1 { "" } else { "s" })
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
+pub struct ReleasePlan(Vec<PackageRelease>);
+
+impl ReleasePlan {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackageRelease> {
+        self.0.iter()
+    }
+
+    /// The next version of each released package.
+    pub fn versions(&self) -> BTreeMap<String, Version> {
+        self.0
+            .iter()
+            .filter_map(|release| {
+                Version::parse(&release.next)
+                    .ok()
+                    .map(|version| (release.name.to_owned(), version))
+            })
+            .collect()
+    }
+}
+
+impl Display for ReleasePlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header = ["Package", "Current", "Next", "Release", "Reason"];
+        let rows: Vec<[String; 5]> = self
+            .0
+            .iter()
+            .map(|release| {
+                [
+                    release.name.to_owned(),
+                    release.current.to_owned(),
+                    release.next.to_owned(),
+                    release.release.to_string(),
+                    release.reason(),
+                ]
+            })
+            .collect();
+        let mut widths = header.map(str::len);
+        for row in rows.iter() {
+            for (width, cell) in widths.iter_mut().zip(row.iter()) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut write_row = |cells: [&str; 5]| -> std::fmt::Result {
+            let line = cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, width)| format!("{:width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(f, "{}", line.trim_end())
+        };
+        write_row(header)?;
+        for row in rows.iter() {
+            write_row(row.each_ref().map(String::as_str))?;
+        }
+        Ok(())
+    }
+}
+
+/// Plan the next release of each internal package from the conventional
+/// commits changing it since its last `<name>@<version>` tag. Internal
+/// packages depending on a released package receive a patch release so
+/// they can be pinned to the new version.
+pub fn plan<P>(root: P) -> Result<ReleasePlan, ReleaseError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(root: &Path) -> Result<ReleasePlan, ReleaseError> {
+        let lerna_manifest = MonorepoManifest::from_directory(root)?;
+        let package_manifests_by_package_name =
+            lerna_manifest.package_manifests_by_package_name()?;
+        let mut package_commits = package_commits(root, &package_manifests_by_package_name)?;
+
+        // The release of each package, and the dependency it cascaded from
+        let mut releases: BTreeMap<&str, (ReleaseType, Option<&str>)> = BTreeMap::new();
+        let mut release_commits: HashMap<&str, Vec<ReleaseCommit>> = HashMap::new();
+        for package_name in package_manifests_by_package_name.keys() {
+            let commits = package_commits
+                .get(package_name)
+                .map(|package_commits| package_commits.commits.as_slice())
+                .unwrap_or_default();
+            let commits: Vec<ReleaseCommit> = commits
+                .iter()
+                .map(|commit| ReleaseCommit {
+                    hash: commit.hash.to_owned(),
+                    subject: commit.subject.to_owned(),
+                    release: ConventionalCommit::parse(&commit.subject, &commit.body)
+                        .and_then(|conventional_commit| conventional_commit.release_type()),
+                })
+                .collect();
+            if let Some(release) = commits
+                .iter()
+                .filter_map(|commit| commit.release)
+                .max_by_key(|release| release_precedence(*release))
+            {
+                releases.insert(package_name, (release, None));
+            }
+            release_commits.insert(package_name, commits);
+        }
+
+        let dependents = internal_dependents(&package_manifests_by_package_name);
+        cascade_releases(&dependents, &mut releases);
+
+        let mut plan = Vec::new();
+        for (package_name, (release, cascaded_from)) in releases {
+            let package_manifest = &package_manifests_by_package_name[package_name];
+            let current =
+                Version::parse(&package_manifest.contents.version).map_err(|err| ReleaseError {
+                    kind: ReleaseErrorKind::InvalidVersion(package_name.to_owned(), err),
+                })?;
+            plan.push(PackageRelease {
+                name: package_name.to_owned(),
+                current: current.to_string(),
+                next: current.increment(release, None).to_string(),
+                release,
+                last_tag: package_commits
+                    .remove(package_name)
+                    .and_then(|package_commits| package_commits.last_tag),
+                commits: release_commits.remove(package_name).unwrap_or_default(),
+                cascaded_from: cascaded_from.map(ToOwned::to_owned),
+            });
+        }
+        Ok(ReleasePlan(plan))
+    }
+    inner(root.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{last_tags, owning_package};
+
+    #[test]
+    fn should_find_last_tag_by_version() {
+        let tags: Vec<String> = [
+            "@scope/a@1.10.0",
+            "@scope/a@1.9.0",
+            "@scope/a@2.0.0-rc.1",
+            "b@0.1.0",
+            "v1.0.0",
+            "@scope/a@not-a-version",
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let last_tags = last_tags(&tags);
+        assert_eq!(
+            Some("@scope/a@2.0.0-rc.1"),
+            last_tags.get("@scope/a").map(|t| t.1)
+        );
+        assert_eq!(Some("b@0.1.0"), last_tags.get("b").map(|t| t.1));
+        assert_eq!(2, last_tags.len());
+    }
+
+    #[test]
+    fn should_attribute_files_to_nested_packages() {
+        let directories = vec![
+            (PathBuf::from("packages/a"), "a"),
+            (PathBuf::from("packages/a/nested"), "nested"),
+            (PathBuf::from("packages/ab"), "ab"),
+        ];
+        let owner = |file: &str| owning_package(&directories, Path::new(file));
+        assert_eq!(Some("a"), owner("packages/a/src/index.ts"));
+        assert_eq!(Some("nested"), owner("packages/a/nested/index.ts"));
+        assert_eq!(Some("ab"), owner("packages/ab/index.ts"));
+        assert_eq!(None, owner("README.md"));
+    }
+}
@@ -0,0 +1,987 @@
+//! Versions and version ranges following the semantics of npm's `semver`
+//! package, which differ from Cargo's interpretation of the same syntax.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use serde::Serialize;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct SemverParseError {
+    pub input: String,
+}
+
+impl Display for SemverParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unable to parse semantic version: {:?}", self.input)
+    }
+}
+
+impl std::error::Error for SemverParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PrereleaseIdentifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl Ord for PrereleaseIdentifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (PrereleaseIdentifier::Numeric(a), PrereleaseIdentifier::Numeric(b)) => a.cmp(b),
+            (PrereleaseIdentifier::Numeric(_), PrereleaseIdentifier::AlphaNumeric(_)) => {
+                Ordering::Less
+            }
+            (PrereleaseIdentifier::AlphaNumeric(_), PrereleaseIdentifier::Numeric(_)) => {
+                Ordering::Greater
+            }
+            (PrereleaseIdentifier::AlphaNumeric(a), PrereleaseIdentifier::AlphaNumeric(b)) => {
+                a.cmp(b)
+            }
+        }
+    }
+}
+
+impl PartialOrd for PrereleaseIdentifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for PrereleaseIdentifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PrereleaseIdentifier::Numeric(n) => write!(f, "{}", n),
+            PrereleaseIdentifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// A semantic version. Build metadata is discarded since it does not
+/// participate in version precedence.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub prerelease: Vec<PrereleaseIdentifier>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            prerelease: Vec::new(),
+        }
+    }
+
+    /// The lowest prerelease of this version, such as `2.0.0-0`, used as an
+    /// exclusive upper bound which also excludes prereleases of this version.
+    fn lowest_prerelease(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            prerelease: vec![PrereleaseIdentifier::Numeric(0)],
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Version, SemverParseError> {
+        let error = || SemverParseError {
+            input: input.to_owned(),
+        };
+        let trimmed = input.trim();
+        let trimmed = trimmed.strip_prefix('=').unwrap_or(trimmed);
+        let trimmed = trimmed.strip_prefix('v').unwrap_or(trimmed);
+        let without_build = trimmed.split('+').next().unwrap_or_default();
+        let (core, prerelease) = match without_build.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease)),
+            None => (without_build, None),
+        };
+
+        let numbers = core
+            .split('.')
+            .map(|part| part.parse::<u64>().map_err(|_| error()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (major, minor, patch) = match numbers[..] {
+            [major, minor, patch] => (major, minor, patch),
+            _ => return Err(error()),
+        };
+
+        let prerelease = match prerelease {
+            Some(prerelease) => prerelease
+                .split('.')
+                .map(|identifier| {
+                    if identifier.is_empty() {
+                        return Err(error());
+                    }
+                    Ok(match identifier.parse::<u64>() {
+                        Ok(n) => PrereleaseIdentifier::Numeric(n),
+                        Err(_) => PrereleaseIdentifier::AlphaNumeric(identifier.to_owned()),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+
+    fn release_triple(&self) -> (u64, u64, u64) {
+        (self.major, self.minor, self.patch)
+    }
+
+    /// The next version of the specified release type, as computed by
+    /// `npm version`. Releasing a prerelease of the same release type
+    /// removes its prerelease identifiers, and a prerelease is started from
+    /// the next patch, identified by `preid` when specified.
+    pub fn increment(&self, release: ReleaseType, preid: Option<&str>) -> Version {
+        let is_prerelease = !self.prerelease.is_empty();
+        match release {
+            ReleaseType::Major if is_prerelease && self.minor == 0 && self.patch == 0 => {
+                Version::new(self.major, 0, 0)
+            }
+            ReleaseType::Major => Version::new(self.major + 1, 0, 0),
+            ReleaseType::Minor if is_prerelease && self.patch == 0 => {
+                Version::new(self.major, self.minor, 0)
+            }
+            ReleaseType::Minor => Version::new(self.major, self.minor + 1, 0),
+            ReleaseType::Patch if is_prerelease => Version::new(self.major, self.minor, self.patch),
+            ReleaseType::Patch => Version::new(self.major, self.minor, self.patch + 1),
+            ReleaseType::Prerelease => {
+                let mut next = match is_prerelease {
+                    true => self.clone(),
+                    false => Version::new(self.major, self.minor, self.patch + 1),
+                };
+                let preid = preid.map(|preid| PrereleaseIdentifier::AlphaNumeric(preid.to_owned()));
+                let same_preid = match (&preid, next.prerelease.first()) {
+                    (Some(preid), Some(first)) => preid == first,
+                    (Some(_), None) => false,
+                    (None, _) => true,
+                };
+                if !same_preid {
+                    next.prerelease = preid.into_iter().collect();
+                }
+                match next
+                    .prerelease
+                    .iter_mut()
+                    .rev()
+                    .find_map(|identifier| match identifier {
+                        PrereleaseIdentifier::Numeric(n) => Some(n),
+                        PrereleaseIdentifier::AlphaNumeric(_) => None,
+                    }) {
+                    Some(n) if is_prerelease && same_preid => *n += 1,
+                    _ => next.prerelease.push(PrereleaseIdentifier::Numeric(0)),
+                }
+                next
+            }
+        }
+    }
+}
+
+/// The kind of release by which to increment a version.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseType {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+}
+
+impl Display for ReleaseType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReleaseType::Major => write!(f, "major"),
+            ReleaseType::Minor => write!(f, "minor"),
+            ReleaseType::Patch => write!(f, "patch"),
+            ReleaseType::Prerelease => write!(f, "prerelease"),
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.release_triple()
+            .cmp(&other.release_triple())
+            .then_with(|| {
+                // A version without prerelease identifiers has higher precedence
+                match (self.prerelease.is_empty(), other.prerelease.is_empty()) {
+                    (true, true) => Ordering::Equal,
+                    (true, false) => Ordering::Greater,
+                    (false, true) => Ordering::Less,
+                    (false, false) => self.prerelease.cmp(&other.prerelease),
+                }
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        for (index, identifier) in self.prerelease.iter().enumerate() {
+            let separator = if index == 0 { '-' } else { '.' };
+            write!(f, "{}{}", separator, identifier)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum Operator {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let operator = match self {
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Eq => "",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Comparator {
+    pub operator: Operator,
+    pub version: Version,
+}
+
+impl Comparator {
+    fn new(operator: Operator, version: Version) -> Self {
+        Self { operator, version }
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.operator {
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Eq => ordering == Ordering::Equal,
+        }
+    }
+}
+
+impl Display for Comparator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.operator, self.version)
+    }
+}
+
+/// A version which may omit trailing components or use wildcards, such as
+/// `1`, `1.2.x` or `*`.
+#[derive(Clone, Debug)]
+struct PartialVersion {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    prerelease: Vec<PrereleaseIdentifier>,
+}
+
+impl PartialVersion {
+    fn parse(input: &str) -> Result<PartialVersion, SemverParseError> {
+        let error = || SemverParseError {
+            input: input.to_owned(),
+        };
+        let trimmed = input.strip_prefix('v').unwrap_or(input);
+        if trimmed.is_empty() {
+            return Ok(PartialVersion {
+                major: None,
+                minor: None,
+                patch: None,
+                prerelease: Vec::new(),
+            });
+        }
+
+        let without_build = trimmed.split('+').next().unwrap_or_default();
+        let (core, prerelease) = match without_build.split_once('-') {
+            Some((core, prerelease)) => (core, Some(prerelease)),
+            None => (without_build, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| match part {
+                "x" | "X" | "*" => Ok(None),
+                _ => part.parse::<u64>().map(Some).map_err(|_| error()),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if parts.len() > 3 {
+            return Err(error());
+        }
+        let part = |index: usize| parts.get(index).copied().flatten();
+        // A wildcard makes every following component a wildcard too
+        let major = part(0);
+        let minor = major.and(part(1));
+        let patch = minor.and(part(2));
+
+        let prerelease = match (prerelease, patch) {
+            (Some(prerelease), Some(_)) => {
+                Version::parse(&format!("0.0.0-{}", prerelease))
+                    .map_err(|_| error())?
+                    .prerelease
+            }
+            (Some(_), None) => return Err(error()),
+            (None, _) => Vec::new(),
+        };
+
+        Ok(PartialVersion {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+
+    /// The lowest version matching this partial version.
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            prerelease: self.prerelease.clone(),
+        }
+    }
+
+    /// The exclusive upper bound of versions matching this partial version,
+    /// or `None` if it is a complete version or matches everything.
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (Some(major), None, _) => Some(Version::lowest_prerelease(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => {
+                Some(Version::lowest_prerelease(major, minor + 1, 0))
+            }
+            _ => None,
+        }
+    }
+
+    fn is_wildcard(&self) -> bool {
+        self.major.is_none()
+    }
+}
+
+/// Desugar a single range token (such as `^1.2.3`, `~1.2`, `>=1` or `1.x`)
+/// into primitive comparators.
+fn parse_comparators(token: &str) -> Result<Vec<Comparator>, SemverParseError> {
+    let (operator, rest) = ["~>", ">=", "<=", ">", "<", "=", "~", "^"]
+        .iter()
+        .find_map(|operator| {
+            token
+                .strip_prefix(operator)
+                .map(|rest| (*operator, rest.trim()))
+        })
+        .unwrap_or(("", token));
+    let partial = PartialVersion::parse(rest)?;
+    let floor = partial.floor();
+    let any = || vec![Comparator::new(Operator::Ge, Version::new(0, 0, 0))];
+    let none = || {
+        vec![Comparator::new(
+            Operator::Lt,
+            Version::lowest_prerelease(0, 0, 0),
+        )]
+    };
+    let between = |lower: Version, upper: Version| {
+        vec![
+            Comparator::new(Operator::Ge, lower),
+            Comparator::new(Operator::Lt, upper),
+        ]
+    };
+
+    let comparators = match operator {
+        "" | "=" => match (partial.is_wildcard(), partial.ceiling()) {
+            (true, _) => any(),
+            (false, Some(ceiling)) => between(floor, ceiling),
+            (false, None) => vec![Comparator::new(Operator::Eq, floor)],
+        },
+        "~" | "~>" => match (partial.major, partial.minor) {
+            (None, _) => any(),
+            (Some(major), None) => between(floor, Version::lowest_prerelease(major + 1, 0, 0)),
+            (Some(major), Some(minor)) => {
+                between(floor, Version::lowest_prerelease(major, minor + 1, 0))
+            }
+        },
+        "^" => match (partial.major, partial.minor, partial.patch) {
+            (None, _, _) => any(),
+            (Some(major), _, _) if major > 0 => {
+                between(floor, Version::lowest_prerelease(major + 1, 0, 0))
+            }
+            (Some(0), None, _) => between(floor, Version::lowest_prerelease(1, 0, 0)),
+            (Some(0), Some(minor), _) if minor > 0 => {
+                between(floor, Version::lowest_prerelease(0, minor + 1, 0))
+            }
+            (Some(0), Some(0), None) => between(floor, Version::lowest_prerelease(0, 1, 0)),
+            (Some(0), Some(0), Some(patch)) => {
+                between(floor, Version::lowest_prerelease(0, 0, patch + 1))
+            }
+            _ => unreachable!("every caret range is covered above"),
+        },
+        ">=" => match partial.is_wildcard() {
+            true => any(),
+            false => vec![Comparator::new(Operator::Ge, floor)],
+        },
+        ">" => match (partial.is_wildcard(), partial.ceiling()) {
+            (true, _) => none(),
+            (false, Some(ceiling)) => vec![Comparator::new(Operator::Ge, ceiling)],
+            (false, None) => vec![Comparator::new(Operator::Gt, floor)],
+        },
+        "<" => match partial.is_wildcard() {
+            true => none(),
+            false => vec![Comparator::new(
+                Operator::Lt,
+                match partial.patch {
+                    Some(_) => floor,
+                    None => Version {
+                        prerelease: vec![PrereleaseIdentifier::Numeric(0)],
+                        ..floor
+                    },
+                },
+            )],
+        },
+        "<=" => match (partial.is_wildcard(), partial.ceiling()) {
+            (true, _) => any(),
+            (false, Some(ceiling)) => vec![Comparator::new(Operator::Lt, ceiling)],
+            (false, None) => vec![Comparator::new(Operator::Le, floor)],
+        },
+        _ => unreachable!("operator list is exhaustive"),
+    };
+    Ok(comparators)
+}
+
+/// A set of comparators which must all match a version.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ComparatorSet(pub Vec<Comparator>);
+
+impl ComparatorSet {
+    fn parse(input: &str) -> Result<ComparatorSet, SemverParseError> {
+        // Hyphen ranges, such as `1.2.3 - 2.3.4`
+        if let Some((lower, upper)) = input.split_once(" - ") {
+            let lower = PartialVersion::parse(lower.trim())?;
+            let upper = PartialVersion::parse(upper.trim())?;
+            let mut comparators = Vec::new();
+            if !lower.is_wildcard() {
+                comparators.push(Comparator::new(Operator::Ge, lower.floor()));
+            }
+            if !upper.is_wildcard() {
+                comparators.push(match upper.ceiling() {
+                    Some(ceiling) => Comparator::new(Operator::Lt, ceiling),
+                    None => Comparator::new(Operator::Le, upper.floor()),
+                });
+            }
+            if comparators.is_empty() {
+                comparators.push(Comparator::new(Operator::Ge, Version::new(0, 0, 0)));
+            }
+            return Ok(ComparatorSet(comparators));
+        }
+
+        // Join operators separated from their version by whitespace
+        let mut tokens: Vec<String> = Vec::new();
+        let mut pending_operator: Option<&str> = None;
+        for token in input.split_whitespace() {
+            let is_bare_operator = ["~>", ">=", "<=", ">", "<", "=", "~", "^"].contains(&token);
+            match (pending_operator.take(), is_bare_operator) {
+                (Some(operator), false) => tokens.push(format!("{}{}", operator, token)),
+                (Some(operator), true) => {
+                    tokens.push(operator.to_owned());
+                    pending_operator = Some(token);
+                }
+                (None, true) => pending_operator = Some(token),
+                (None, false) => tokens.push(token.to_owned()),
+            }
+        }
+        if let Some(operator) = pending_operator {
+            tokens.push(operator.to_owned());
+        }
+        if tokens.is_empty() {
+            tokens.push(String::from("*"));
+        }
+
+        let comparators = tokens
+            .iter()
+            .map(|token| parse_comparators(token))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok(ComparatorSet(comparators))
+    }
+
+    fn satisfies(&self, version: &Version) -> bool {
+        if !self.0.iter().all(|comparator| comparator.matches(version)) {
+            return false;
+        }
+        // Prereleases only match when a comparator opts into prereleases of
+        // the same release
+        version.prerelease.is_empty()
+            || self.0.iter().any(|comparator| {
+                !comparator.version.prerelease.is_empty()
+                    && comparator.version.release_triple() == version.release_triple()
+            })
+    }
+}
+
+impl ComparatorSet {
+    /// The comparators matching exactly the versions matched by every
+    /// comparator of this set, keeping only the tightest lower and upper
+    /// bound.
+    fn simplify(&self) -> ComparatorSet {
+        let mut lower: Option<Comparator> = None;
+        let mut upper: Option<Comparator> = None;
+        for comparator in self.0.iter() {
+            // At equal versions, an exclusive bound is the tighter one
+            let is_lower_tighter = |bound: &Comparator| {
+                comparator
+                    .version
+                    .cmp(&bound.version)
+                    .then_with(|| match comparator.operator {
+                        Operator::Gt => Ordering::Greater,
+                        _ => Ordering::Equal,
+                    })
+                    == Ordering::Greater
+            };
+            let is_upper_tighter = |bound: &Comparator| {
+                comparator
+                    .version
+                    .cmp(&bound.version)
+                    .then_with(|| match comparator.operator {
+                        Operator::Lt => Ordering::Less,
+                        _ => Ordering::Equal,
+                    })
+                    == Ordering::Less
+            };
+            let (is_lower, is_upper) = match comparator.operator {
+                Operator::Gt | Operator::Ge => (true, false),
+                Operator::Lt | Operator::Le => (false, true),
+                Operator::Eq => (true, true),
+            };
+            if is_lower && lower.as_ref().is_none_or(is_lower_tighter) {
+                let operator = match comparator.operator {
+                    Operator::Gt => Operator::Gt,
+                    _ => Operator::Ge,
+                };
+                lower = Some(Comparator::new(operator, comparator.version.clone()));
+            }
+            if is_upper && upper.as_ref().is_none_or(is_upper_tighter) {
+                let operator = match comparator.operator {
+                    Operator::Lt => Operator::Lt,
+                    _ => Operator::Le,
+                };
+                upper = Some(Comparator::new(operator, comparator.version.clone()));
+            }
+        }
+
+        match (lower, upper) {
+            (Some(lower), Some(upper))
+                if lower.operator == Operator::Ge
+                    && upper.operator == Operator::Le
+                    && lower.version == upper.version =>
+            {
+                ComparatorSet(vec![Comparator::new(Operator::Eq, lower.version)])
+            }
+            (None, None) => {
+                ComparatorSet(vec![Comparator::new(Operator::Ge, Version::new(0, 0, 0))])
+            }
+            (lower, upper) => ComparatorSet(lower.into_iter().chain(upper).collect()),
+        }
+    }
+
+    /// Whether any version satisfies this set, decided by testing the
+    /// versions adjacent to each bound.
+    fn is_satisfiable(&self) -> bool {
+        let mut candidates = vec![Version::new(0, 0, 0)];
+        for comparator in self.0.iter() {
+            let version = &comparator.version;
+            let mut next_prerelease = version.clone();
+            next_prerelease
+                .prerelease
+                .push(PrereleaseIdentifier::Numeric(0));
+            candidates.extend([
+                version.clone(),
+                next_prerelease,
+                Version::new(version.major, version.minor, version.patch),
+                Version::new(version.major, version.minor, version.patch + 1),
+            ]);
+        }
+        candidates.iter().any(|candidate| self.satisfies(candidate))
+    }
+
+    /// The set of versions satisfying both sets, or `None` if there are
+    /// none.
+    fn intersect(&self, other: &ComparatorSet) -> Option<ComparatorSet> {
+        let intersection =
+            ComparatorSet(self.0.iter().chain(other.0.iter()).cloned().collect()).simplify();
+        match intersection.is_satisfiable() {
+            true => Some(intersection),
+            false => None,
+        }
+    }
+
+    /// The lower and upper bound of a simplified set, each with whether it
+    /// is inclusive. A missing lower bound is `>=0.0.0`.
+    fn bounds(&self) -> ((&Version, bool), Option<(&Version, bool)>) {
+        const ZERO: &Version = &Version {
+            major: 0,
+            minor: 0,
+            patch: 0,
+            prerelease: Vec::new(),
+        };
+        let mut lower = (ZERO, true);
+        let mut upper = None;
+        for comparator in self.0.iter() {
+            let version = &comparator.version;
+            match comparator.operator {
+                Operator::Gt => lower = (version, false),
+                Operator::Ge => lower = (version, true),
+                Operator::Lt => upper = Some((version, false)),
+                Operator::Le => upper = Some((version, true)),
+                Operator::Eq => {
+                    lower = (version, true);
+                    upper = Some((version, true));
+                }
+            }
+        }
+        (lower, upper)
+    }
+
+    /// Whether every version satisfying this set satisfies `other`, decided
+    /// by comparing the bounds of both sets.
+    fn is_subset(&self, other: &ComparatorSet) -> bool {
+        let this = self.simplify();
+        if !this.is_satisfiable() {
+            return true;
+        }
+        let other = other.simplify();
+        let ((lower, is_lower_inclusive), upper) = this.bounds();
+        let ((other_lower, is_other_lower_inclusive), other_upper) = other.bounds();
+        let is_lower_within = match lower.cmp(other_lower) {
+            Ordering::Greater => true,
+            Ordering::Equal => is_other_lower_inclusive || !is_lower_inclusive,
+            Ordering::Less => false,
+        };
+        let is_upper_within = match (upper, other_upper) {
+            (_, None) => true,
+            (None, Some(_)) => false,
+            (Some((upper, is_upper_inclusive)), Some((other_upper, is_other_upper_inclusive))) => {
+                match upper.cmp(other_upper) {
+                    Ordering::Less => true,
+                    Ordering::Equal => is_other_upper_inclusive || !is_upper_inclusive,
+                    Ordering::Greater => false,
+                }
+            }
+        };
+        is_lower_within && is_upper_within
+    }
+
+    /// The caret or tilde shorthand equivalent to this set, such as `^1.3.0`
+    /// for `>=1.3.0 <2.0.0-0`.
+    fn shorthand(&self) -> Option<String> {
+        let (floor, ceiling) = match &self.0[..] {
+            [Comparator {
+                operator: Operator::Ge,
+                version: floor,
+            }, Comparator {
+                operator: Operator::Lt,
+                version: ceiling,
+            }] => (floor, ceiling),
+            _ => return None,
+        };
+        let caret_ceiling = match (floor.major, floor.minor, floor.patch) {
+            (0, 0, patch) => Version::lowest_prerelease(0, 0, patch + 1),
+            (0, minor, _) => Version::lowest_prerelease(0, minor + 1, 0),
+            (major, _, _) => Version::lowest_prerelease(major + 1, 0, 0),
+        };
+        let tilde_ceiling = Version::lowest_prerelease(floor.major, floor.minor + 1, 0);
+        if ceiling == &caret_ceiling {
+            Some(format!("^{}", floor))
+        } else if ceiling == &tilde_ceiling {
+            Some(format!("~{}", floor))
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for ComparatorSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, comparator) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", comparator)?;
+        }
+        Ok(())
+    }
+}
+
+/// A version range, such as `^1.2.3 || >=2.0.0 <3.0.0`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Range(pub Vec<ComparatorSet>);
+
+impl Range {
+    pub fn parse(input: &str) -> Result<Range, SemverParseError> {
+        let comparator_sets = input
+            .split("||")
+            .map(|comparator_set| {
+                ComparatorSet::parse(comparator_set.trim()).map_err(|_| SemverParseError {
+                    input: input.to_owned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Range(comparator_sets))
+    }
+
+    pub fn satisfies(&self, version: &Version) -> bool {
+        self.0
+            .iter()
+            .any(|comparator_set| comparator_set.satisfies(version))
+    }
+
+    /// The narrowest range satisfied by exactly the versions satisfying both
+    /// ranges, or `None` if no version satisfies both.
+    pub fn intersect(&self, other: &Range) -> Option<Range> {
+        let mut comparator_sets: Vec<ComparatorSet> = Vec::new();
+        for comparator_set in self.0.iter() {
+            for other_comparator_set in other.0.iter() {
+                if let Some(intersection) = comparator_set.intersect(other_comparator_set) {
+                    if !comparator_sets.contains(&intersection) {
+                        comparator_sets.push(intersection);
+                    }
+                }
+            }
+        }
+        match comparator_sets.is_empty() {
+            true => None,
+            false => Some(Range(comparator_sets)),
+        }
+    }
+
+    /// Whether every version satisfying this range satisfies `other`. Each
+    /// comparator set must lie within a single comparator set of `other`, so
+    /// a set covered only by the union of several is not recognized.
+    pub fn is_subset(&self, other: &Range) -> bool {
+        self.0.iter().all(|comparator_set| {
+            other
+                .0
+                .iter()
+                .any(|other_comparator_set| comparator_set.is_subset(other_comparator_set))
+        })
+    }
+
+    /// Render this range with caret and tilde shorthands where possible,
+    /// such as `^1.3.0` rather than `>=1.3.0 <2.0.0-0`.
+    pub fn to_shorthand(&self) -> String {
+        self.0
+            .iter()
+            .map(|comparator_set| {
+                comparator_set
+                    .shorthand()
+                    .unwrap_or_else(|| comparator_set.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" || ")
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (index, comparator_set) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, " || ")?;
+            }
+            write!(f, "{}", comparator_set)?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a version specifier into its leading range operator and the rest,
+/// for example `^1.2.3` into `^` and `1.2.3`.
+pub fn split_operator(specifier: &str) -> (&str, &str) {
+    ["~>", ">=", "<=", ">", "<", "=", "~", "^"]
+        .iter()
+        .find_map(|operator| {
+            specifier
+                .strip_prefix(operator)
+                .map(|rest| (*operator, rest.trim_start()))
+        })
+        .unwrap_or(("", specifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Range, ReleaseType, Version};
+
+    fn satisfies(range: &str, version: &str) -> bool {
+        Range::parse(range)
+            .unwrap()
+            .satisfies(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn should_order_versions_by_precedence() {
+        let mut versions: Vec<Version> = [
+            "1.0.0",
+            "1.0.0-rc.1",
+            "1.0.0-beta.11",
+            "1.0.0-beta.2",
+            "1.0.0-alpha",
+            "0.9.0",
+        ]
+        .iter()
+        .map(|version| Version::parse(version).unwrap())
+        .collect();
+        versions.sort();
+        assert_eq!(
+            vec![
+                "0.9.0",
+                "1.0.0-alpha",
+                "1.0.0-beta.2",
+                "1.0.0-beta.11",
+                "1.0.0-rc.1",
+                "1.0.0"
+            ],
+            versions.iter().map(ToString::to_string).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn should_match_caret_ranges() {
+        assert!(satisfies("^1.2.0", "1.3.0"));
+        assert!(!satisfies("^1.2.0", "2.0.0"));
+        assert!(!satisfies("^1.2.0", "2.0.0-alpha"));
+        assert!(satisfies("^0.2.3", "0.2.9"));
+        assert!(!satisfies("^0.2.3", "0.3.0"));
+        assert!(!satisfies("^0.0.3", "0.0.4"));
+    }
+
+    #[test]
+    fn should_match_tilde_and_x_ranges() {
+        assert!(satisfies("~1.2.3", "1.2.9"));
+        assert!(!satisfies("~1.2.3", "1.3.0"));
+        assert!(satisfies("1.2.x", "1.2.7"));
+        assert!(satisfies("1", "1.9.0"));
+        assert!(satisfies("*", "3.0.0"));
+        assert!(satisfies("", "3.0.0"));
+    }
+
+    #[test]
+    fn should_match_primitive_and_hyphen_ranges() {
+        assert!(satisfies(">= 1.2.3 < 2", "1.9.0"));
+        assert!(!satisfies(">1.2", "1.2.9"));
+        assert!(satisfies("<=1.2", "1.2.9"));
+        assert!(satisfies("1.2.3 - 2.3", "2.3.9"));
+        assert!(!satisfies("1.2.3 - 2.3.4", "2.3.5"));
+        assert!(satisfies("<1.0.0 || >=2.0.0", "2.1.0"));
+    }
+
+    fn intersect(a: &str, b: &str) -> Option<String> {
+        Range::parse(a)
+            .unwrap()
+            .intersect(&Range::parse(b).unwrap())
+            .map(|range| range.to_shorthand())
+    }
+
+    #[test]
+    fn should_intersect_ranges() {
+        assert_eq!(Some(String::from("^1.3.0")), intersect("^1.2.0", "^1.3.0"));
+        assert_eq!(Some(String::from("~1.2.4")), intersect("1.2.x", "^1.2.4"));
+        assert_eq!(Some(String::from("1.2.5")), intersect("~1.2.0", "1.2.5"));
+        assert_eq!(
+            Some(String::from(">=1.5.0 <=1.9.0")),
+            intersect(">=1.5.0", "^1.2.0 <=1.9.0")
+        );
+        assert_eq!(
+            Some(String::from("^2.0.0")),
+            intersect("^1.0.0 || ^2.0.0", "2")
+        );
+        assert_eq!(None, intersect("^1.2.0", "^2.0.0"));
+        assert_eq!(None, intersect(">1.2.3", "<=1.2.3"));
+    }
+
+    fn is_subset(a: &str, b: &str) -> bool {
+        Range::parse(a)
+            .unwrap()
+            .is_subset(&Range::parse(b).unwrap())
+    }
+
+    #[test]
+    fn should_detect_subranges() {
+        assert!(is_subset("^18.3.0", "^18.2.0"));
+        assert!(is_subset("~18.2.4", "^18.2.0"));
+        assert!(is_subset("18.2.5", "^18.2.0"));
+        assert!(is_subset(">=1.2.0 <1.5.0", ">=1.0.0 <=1.5.0"));
+        assert!(is_subset("^1.2.0 || ^2.1.0", "^1.0.0 || ^2.0.0"));
+        assert!(is_subset("^1.2.0", "*"));
+        assert!(!is_subset("^18.0.0", "^18.2.0"));
+        assert!(!is_subset("^18.2.0", "~18.2.0"));
+        assert!(!is_subset(">=18.2.0", "^18.2.0"));
+        assert!(!is_subset("<=1.5.0", "<1.5.0"));
+        assert!(!is_subset("^1.2.0 || ^3.0.0", "^1.0.0 || ^2.0.0"));
+    }
+
+    #[test]
+    fn should_increment_versions() {
+        let increment = |version: &str, release: ReleaseType, preid: Option<&str>| {
+            Version::parse(version)
+                .unwrap()
+                .increment(release, preid)
+                .to_string()
+        };
+        assert_eq!("2.0.0", increment("1.2.3", ReleaseType::Major, None));
+        assert_eq!("2.0.0", increment("2.0.0-rc.1", ReleaseType::Major, None));
+        assert_eq!("1.3.0", increment("1.2.3", ReleaseType::Minor, None));
+        assert_eq!("1.3.0", increment("1.3.0-0", ReleaseType::Minor, None));
+        assert_eq!("1.2.4", increment("1.2.3", ReleaseType::Patch, None));
+        assert_eq!("1.2.3", increment("1.2.3-0", ReleaseType::Patch, None));
+        assert_eq!("1.2.4-0", increment("1.2.3", ReleaseType::Prerelease, None));
+        assert_eq!(
+            "1.2.4-beta.0",
+            increment("1.2.3", ReleaseType::Prerelease, Some("beta"))
+        );
+        assert_eq!(
+            "1.2.4-beta.1",
+            increment("1.2.4-beta.0", ReleaseType::Prerelease, Some("beta"))
+        );
+        assert_eq!(
+            "1.2.4-rc.0",
+            increment("1.2.4-beta.3", ReleaseType::Prerelease, Some("rc"))
+        );
+        assert_eq!(
+            "1.2.4-beta.0",
+            increment("1.2.4-beta", ReleaseType::Prerelease, None)
+        );
+    }
+
+    #[test]
+    fn should_only_match_prereleases_when_opted_in() {
+        assert!(satisfies("^1.2.3-beta.1", "1.2.3-beta.2"));
+        assert!(!satisfies("^1.2.3-beta.1", "1.2.4-beta.2"));
+        assert!(!satisfies("*", "1.0.0-alpha"));
+    }
+}
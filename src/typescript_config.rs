@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 
 use crate::configuration_file::ConfigurationFile;
-use crate::io::{read_json_from_file, FromFileError};
+use crate::io::{normalize_path, read_json_from_file, FromFileError};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub struct TypescriptProjectReference {
@@ -88,3 +88,176 @@ impl ConfigurationFile for TypescriptConfig {
         &self.contents
     }
 }
+
+impl TypescriptConfig {
+    /// Project references currently declared in this tsconfig file.
+    pub fn project_references(&self) -> Vec<TypescriptProjectReference> {
+        self.contents
+            .get("references")
+            .map(|value| {
+                serde_json::from_value::<Vec<TypescriptProjectReference>>(value.clone())
+                    .expect("value starting as JSON should be deserializable")
+            })
+            .unwrap_or_default()
+    }
+
+    /// Follow this tsconfig file's `extends` chain to determine the settings
+    /// the TypeScript compiler will use.
+    pub fn resolve(&self, monorepo_root: &Path) -> Result<ResolvedTypescriptConfig, FromFileError> {
+        let path = self.path();
+        resolve_typescript_config(
+            monorepo_root,
+            &path,
+            &self.contents,
+            &mut vec![path.clone()],
+        )
+    }
+}
+
+/// Settings of a tsconfig file after following its `extends` chain, with all
+/// paths expressed relative to the monorepo root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ResolvedTypescriptConfig {
+    /// Merged `compilerOptions`, as declared (paths are not resolved).
+    pub compiler_options: serde_json::Map<String, serde_json::Value>,
+    pub root_dir: Option<PathBuf>,
+    pub out_dir: Option<PathBuf>,
+    pub declaration_dir: Option<PathBuf>,
+    pub files: Option<Vec<PathBuf>>,
+    pub include: Option<Vec<PathBuf>>,
+    pub exclude: Option<Vec<PathBuf>>,
+}
+
+impl ResolvedTypescriptConfig {
+    /// Overlay the settings of an extending config onto these settings.
+    fn merge(&mut self, other: ResolvedTypescriptConfig) {
+        self.compiler_options.extend(other.compiler_options);
+        self.root_dir = other.root_dir.or(self.root_dir.take());
+        self.out_dir = other.out_dir.or(self.out_dir.take());
+        self.declaration_dir = other.declaration_dir.or(self.declaration_dir.take());
+        self.files = other.files.or(self.files.take());
+        self.include = other.include.or(self.include.take());
+        self.exclude = other.exclude.or(self.exclude.take());
+    }
+
+    /// Whether the named boolean compiler option is enabled.
+    pub fn is_enabled(&self, compiler_option: &str) -> bool {
+        self.compiler_options
+            .get(compiler_option)
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(false)
+    }
+}
+
+fn resolve_typescript_config(
+    monorepo_root: &Path,
+    path: &Path,
+    contents: &serde_json::Map<String, serde_json::Value>,
+    visited: &mut Vec<PathBuf>,
+) -> Result<ResolvedTypescriptConfig, FromFileError> {
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    // `extends` is either a single config or, since TypeScript 5.0, a list of configs
+    let extends: Vec<&str> = match contents.get("extends") {
+        Some(serde_json::Value::String(extends)) => vec![extends],
+        Some(serde_json::Value::Array(extends)) => extends
+            .iter()
+            .filter_map(serde_json::Value::as_str)
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut resolved = ResolvedTypescriptConfig::default();
+    for extends in extends {
+        let base_path = match resolve_extends_path(monorepo_root, directory, extends) {
+            Some(base_path) if !visited.contains(&base_path) => base_path,
+            _ => continue,
+        };
+        visited.push(base_path.clone());
+        let base_contents: serde_json::Map<String, serde_json::Value> =
+            read_json_from_file(&monorepo_root.join(&base_path))?;
+        resolved.merge(resolve_typescript_config(
+            monorepo_root,
+            &base_path,
+            &base_contents,
+            visited,
+        )?);
+    }
+
+    // Paths are relative to the config file which declares them
+    let resolve_path = |value: &serde_json::Value| {
+        value
+            .as_str()
+            .map(|relative_path| normalize_path(&directory.join(relative_path)))
+    };
+    let resolve_paths = |key: &str| {
+        contents
+            .get(key)
+            .and_then(serde_json::Value::as_array)
+            .map(|values| values.iter().filter_map(resolve_path).collect())
+    };
+
+    let compiler_options = contents
+        .get("compilerOptions")
+        .and_then(serde_json::Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let own = ResolvedTypescriptConfig {
+        root_dir: compiler_options.get("rootDir").and_then(resolve_path),
+        out_dir: compiler_options.get("outDir").and_then(resolve_path),
+        declaration_dir: compiler_options
+            .get("declarationDir")
+            .and_then(resolve_path),
+        compiler_options,
+        files: resolve_paths("files"),
+        include: resolve_paths("include"),
+        exclude: resolve_paths("exclude"),
+    };
+    resolved.merge(own);
+
+    Ok(resolved)
+}
+
+/// Find the config file named by `extends`, relative to the monorepo root.
+/// Package specifiers are looked up in `node_modules` directories; a package
+/// which is not installed is ignored.
+fn resolve_extends_path(monorepo_root: &Path, directory: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json_extension = |candidate: PathBuf| -> PathBuf {
+        match candidate.extension() {
+            Some(extension) if extension == "json" => candidate,
+            _ => {
+                let mut with_extension = candidate.clone().into_os_string();
+                with_extension.push(".json");
+                match monorepo_root.join(&with_extension).is_file() {
+                    true => with_extension.into(),
+                    false => candidate,
+                }
+            }
+        }
+    };
+
+    if extends.starts_with("./") || extends.starts_with("../") {
+        return Some(with_json_extension(normalize_path(
+            &directory.join(extends),
+        )));
+    }
+    if Path::new(extends).is_absolute() {
+        return None;
+    }
+
+    directory.ancestors().find_map(|ancestor| {
+        let candidate = with_json_extension(ancestor.join("node_modules").join(extends));
+        let absolute_candidate = monorepo_root.join(&candidate);
+        if absolute_candidate.is_file() {
+            Some(candidate)
+        } else if absolute_candidate
+            .join(TypescriptConfig::FILENAME)
+            .is_file()
+        {
+            Some(candidate.join(TypescriptConfig::FILENAME))
+        } else {
+            None
+        }
+    })
+}
@@ -1,11 +1,29 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+use serde::Serialize;
+
+use crate::annotation::{json_property_line, Annotation};
+use crate::io::{read_to_string, FromFileError};
+
+/// Rule identifier of unpinned dependencies in CI annotations.
+pub(crate) const UNPINNED_DEPENDENCY_RULE: &str = "unpinned-dependency";
+
+/// Rule identifier of non-string dependency versions in CI annotations.
+pub(crate) const NON_STRING_VERSION_RULE: &str = "non-string-dependency-version";
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
 #[non_exhaustive]
-pub(crate) struct UnpinnedDependency {
+pub struct UnpinnedDependency {
     pub name: String,
+    pub group: String,
     pub actual: String,
     pub expected: String,
+    /// 1-based line of the dependency in the package manifest, when known.
+    pub line: Option<usize>,
 }
 
 impl Display for UnpinnedDependency {
@@ -18,30 +36,92 @@ impl Display for UnpinnedDependency {
     }
 }
 
-#[derive(Clone, Debug)]
-pub(crate) struct UnpinnedPackageDependencies(PathBuf, Vec<UnpinnedDependency>);
+/// A dependency declared with a value other than a string, which cannot be
+/// pinned.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct NonStringVersion {
+    pub name: String,
+    pub group: String,
+    pub actual: serde_json::Value,
+    /// 1-based line of the dependency in the package manifest, when known.
+    pub line: Option<usize>,
+}
+
+impl Display for NonStringVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "dependency: {}\tgroup: {}\tgot non-string version: {}",
+            self.name, self.group, self.actual
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct UnpinnedPackageDependencies {
+    /// Path to the package manifest, relative to the monorepo root.
+    pub path: PathBuf,
+    pub dependencies: Vec<UnpinnedDependency>,
+    pub non_string_versions: Vec<NonStringVersion>,
+}
+
+impl UnpinnedPackageDependencies {
+    pub(crate) fn new(
+        path: PathBuf,
+        dependencies: Vec<UnpinnedDependency>,
+        non_string_versions: Vec<NonStringVersion>,
+    ) -> Self {
+        Self {
+            path,
+            dependencies,
+            non_string_versions,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dependencies.is_empty() && self.non_string_versions.is_empty()
+    }
+
+    /// Locate every reported dependency in the package manifest, for CI
+    /// annotations.
+    pub(crate) fn locate(&mut self, root: &Path) -> Result<(), FromFileError> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        let contents = read_to_string(&root.join(&self.path))?;
+        for dependency in self.dependencies.iter_mut() {
+            dependency.line = json_property_line(&contents, &[&dependency.group, &dependency.name]);
+        }
+        for dependency in self.non_string_versions.iter_mut() {
+            dependency.line = json_property_line(&contents, &[&dependency.group, &dependency.name]);
+        }
+        Ok(())
+    }
+}
 
 impl Display for UnpinnedPackageDependencies {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(
             f,
             "File contains unpinned dependency versions: {:?}",
-            self.0
+            self.path
         )?;
-        for unpinned_dependency in self.1.iter() {
+        for unpinned_dependency in self.dependencies.iter() {
             writeln!(f, "\t{}", unpinned_dependency)?;
         }
+        for non_string_version in self.non_string_versions.iter() {
+            writeln!(f, "\t{}", non_string_version)?;
+        }
         Ok(())
     }
 }
 
-impl From<(PathBuf, Vec<UnpinnedDependency>)> for UnpinnedPackageDependencies {
-    fn from(value: (PathBuf, Vec<UnpinnedDependency>)) -> Self {
-        Self(value.0, value.1)
-    }
-}
-
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(transparent)]
 pub struct UnpinnedMonorepoDependencies(Vec<UnpinnedPackageDependencies>);
 
 impl Display for UnpinnedMonorepoDependencies {
@@ -59,13 +139,14 @@ impl From<Vec<UnpinnedPackageDependencies>> for UnpinnedMonorepoDependencies {
     }
 }
 
-impl FromIterator<(PathBuf, Vec<UnpinnedDependency>)> for UnpinnedMonorepoDependencies {
-    fn from_iter<T: IntoIterator<Item = (PathBuf, Vec<UnpinnedDependency>)>>(iter: T) -> Self {
-        let collection = iter
+impl FromIterator<UnpinnedPackageDependencies> for UnpinnedMonorepoDependencies {
+    fn from_iter<T: IntoIterator<Item = UnpinnedPackageDependencies>>(iter: T) -> Self {
+        let mut collection: Vec<UnpinnedPackageDependencies> = iter
             .into_iter()
-            .filter(|(_package_name, unpinned_dependencies)| !unpinned_dependencies.is_empty())
-            .map(Into::into)
+            .filter(|package| !package.is_empty())
             .collect();
+        // Sort by path for deterministic output
+        collection.sort_unstable_by(|a, b| a.path.cmp(&b.path));
         Self(collection)
     }
 }
@@ -74,37 +155,98 @@ impl UnpinnedMonorepoDependencies {
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &UnpinnedPackageDependencies> {
+        self.0.iter()
+    }
+
+    /// Number of reported dependencies across all packages.
+    pub fn dependency_count(&self) -> usize {
+        self.0
+            .iter()
+            .map(|package| package.dependencies.len() + package.non_string_versions.len())
+            .sum()
+    }
+
+    /// Describe every reported dependency for CI annotations.
+    pub fn annotations(&self) -> Vec<Annotation> {
+        self.0
+            .iter()
+            .flat_map(|package| {
+                let unpinned = package.dependencies.iter().map(|dependency| {
+                    Annotation::new(
+                        &package.path,
+                        dependency.line,
+                        UNPINNED_DEPENDENCY_RULE,
+                        format!(
+                            "{} in {} should be {:?} but is {:?}",
+                            dependency.name,
+                            dependency.group,
+                            dependency.expected,
+                            dependency.actual
+                        ),
+                    )
+                });
+                let non_string = package.non_string_versions.iter().map(|dependency| {
+                    Annotation::new(
+                        &package.path,
+                        dependency.line,
+                        NON_STRING_VERSION_RULE,
+                        format!(
+                            "{} in {} must be declared with a string but is {}",
+                            dependency.name, dependency.group, dependency.actual
+                        ),
+                    )
+                });
+                unpinned.chain(non_string)
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
 
-    use super::{UnpinnedDependency, UnpinnedMonorepoDependencies, UnpinnedPackageDependencies};
+    use super::{
+        NonStringVersion, UnpinnedDependency, UnpinnedMonorepoDependencies,
+        UnpinnedPackageDependencies,
+    };
 
     #[test]
     fn should_display_all_data() {
-        let unpinned: UnpinnedMonorepoDependencies = vec![UnpinnedPackageDependencies(
-            PathBuf::from("packages/a/package.json"),
-            vec![
+        let unpinned: UnpinnedMonorepoDependencies = vec![UnpinnedPackageDependencies {
+            path: PathBuf::from("packages/a/package.json"),
+            dependencies: vec![
                 UnpinnedDependency {
                     name: "one".into(),
+                    group: "dependencies".into(),
                     actual: "0.0.0".into(),
                     expected: "2.0.0".into(),
+                    line: None,
                 },
                 UnpinnedDependency {
                     name: "two".into(),
+                    group: "devDependencies".into(),
                     actual: "0.0.0".into(),
                     expected: "2.0.0".into(),
+                    line: None,
                 },
             ],
-        )]
+            non_string_versions: vec![NonStringVersion {
+                name: "three".into(),
+                group: "dependencies".into(),
+                actual: serde_json::json!(2),
+                line: None,
+            }],
+        }]
         .into();
 
         let expected = r#"
 File contains unpinned dependency versions: "packages/a/package.json"
 	dependency: one	expected: 2.0.0	got: 0.0.0
 	dependency: two	expected: 2.0.0	got: 0.0.0
+	dependency: three	group: dependencies	got non-string version: 2
 "#
         .trim_start();
         let actual = format!("{}", unpinned);
@@ -0,0 +1,424 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::configuration_file::{write_all, ConfigurationFile, WriteError};
+use crate::io::FromFileError;
+use crate::monorepo_configuration::MonorepoConfiguration;
+use crate::monorepo_manifest::{EnumeratePackageManifestsError, MonorepoManifest};
+use crate::package_manifest::PackageManifest;
+use crate::pin::{pin_package_manifests, PinError, PinErrorKind};
+use crate::semver::{ReleaseType, SemverParseError, Version};
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct VersionError {
+    pub kind: VersionErrorKind,
+}
+
+impl Display for VersionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            VersionErrorKind::UnknownPackage(package_name) => {
+                write!(f, "no internal package named {}", package_name)
+            }
+            VersionErrorKind::InvalidVersion(package_name, _) => {
+                write!(f, "package {} has an invalid version", package_name)
+            }
+            _ => write!(f, "error bumping package versions"),
+        }
+    }
+}
+
+impl std::error::Error for VersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            VersionErrorKind::FromFile(err) => Some(err),
+            VersionErrorKind::EnumeratePackageManifests(err) => Some(err),
+            VersionErrorKind::Pin(err) => Some(err),
+            VersionErrorKind::Write(err) => Some(err),
+            VersionErrorKind::InvalidVersion(_, err) => Some(err),
+            VersionErrorKind::UnknownPackage(_) => None,
+        }
+    }
+}
+
+impl From<FromFileError> for VersionError {
+    fn from(err: FromFileError) -> Self {
+        Self {
+            kind: VersionErrorKind::FromFile(err),
+        }
+    }
+}
+
+impl From<EnumeratePackageManifestsError> for VersionError {
+    fn from(err: EnumeratePackageManifestsError) -> Self {
+        Self {
+            kind: VersionErrorKind::EnumeratePackageManifests(err),
+        }
+    }
+}
+
+impl From<PinError> for VersionError {
+    fn from(err: PinError) -> Self {
+        Self {
+            kind: VersionErrorKind::Pin(err),
+        }
+    }
+}
+
+impl From<WriteError> for VersionError {
+    fn from(err: WriteError) -> Self {
+        Self {
+            kind: VersionErrorKind::Write(err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum VersionErrorKind {
+    #[non_exhaustive]
+    FromFile(FromFileError),
+    #[non_exhaustive]
+    EnumeratePackageManifests(EnumeratePackageManifestsError),
+    #[non_exhaustive]
+    Pin(PinError),
+    #[non_exhaustive]
+    Write(WriteError),
+    #[non_exhaustive]
+    UnknownPackage(String),
+    #[non_exhaustive]
+    InvalidVersion(String, SemverParseError),
+}
+
+/// How to change the version of a package.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Bump {
+    /// Increment the version by a release type, with an optional prerelease
+    /// identifier such as `beta`.
+    Release {
+        release: ReleaseType,
+        preid: Option<String>,
+    },
+    /// Set the version to an explicit version.
+    Explicit(Version),
+}
+
+impl Bump {
+    /// Parse a release type (`major`, `minor`, `patch` or `prerelease`) or an
+    /// explicit version.
+    pub fn parse(input: &str, preid: Option<String>) -> Result<Self, SemverParseError> {
+        let release = match input {
+            "major" => ReleaseType::Major,
+            "minor" => ReleaseType::Minor,
+            "patch" => ReleaseType::Patch,
+            "prerelease" => ReleaseType::Prerelease,
+            version => return Version::parse(version).map(Self::Explicit),
+        };
+        Ok(Self::Release { release, preid })
+    }
+
+    pub fn apply(&self, version: &Version) -> Version {
+        match self {
+            Bump::Release { release, preid } => version.increment(*release, preid.as_deref()),
+            Bump::Explicit(version) => version.clone(),
+        }
+    }
+
+    /// The bump given to internal packages depending on a package bumped
+    /// with this bump: a prerelease for prereleases, otherwise a patch.
+    fn cascaded(&self) -> Bump {
+        match self {
+            Bump::Release {
+                release: ReleaseType::Prerelease,
+                preid,
+            } => Bump::Release {
+                release: ReleaseType::Prerelease,
+                preid: preid.clone(),
+            },
+            _ => Bump::Release {
+                release: ReleaseType::Patch,
+                preid: None,
+            },
+        }
+    }
+}
+
+/// The new version of a package.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct VersionChange {
+    pub name: String,
+    pub current: String,
+    pub next: String,
+    /// The internal dependency whose bump cascaded to this package, or
+    /// `None` when the package was selected.
+    pub cascaded_from: Option<String>,
+}
+
+impl Display for VersionChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} -> {}", self.name, self.current, self.next)?;
+        if let Some(dependency) = &self.cascaded_from {
+            write!(f, " (depends on {})", dependency)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct VersionPlan {
+    pub changes: Vec<VersionChange>,
+    /// Package manifests to write, relative to the monorepo root, including
+    /// internal dependents pinned to the new versions.
+    pub files: Vec<PathBuf>,
+}
+
+impl Display for VersionPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Versions:")?;
+        for change in self.changes.iter() {
+            writeln!(f, "\t{}", change)?;
+        }
+        writeln!(f, "Package manifests:")?;
+        for file in self.files.iter() {
+            writeln!(f, "\t{}", file.display())?;
+        }
+        Ok(())
+    }
+}
+
+/// The internal packages which depend directly on each internal package.
+pub(crate) fn internal_dependents(
+    package_manifests_by_package_name: &HashMap<String, PackageManifest>,
+) -> HashMap<&str, Vec<&str>> {
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for package_manifest in package_manifests_by_package_name.values() {
+        for dependency in
+            package_manifest.internal_dependencies_iter(package_manifests_by_package_name)
+        {
+            dependents
+                .entry(dependency.contents.name.as_str())
+                .or_default()
+                .push(package_manifest.contents.name.as_str());
+        }
+    }
+    for dependents in dependents.values_mut() {
+        dependents.sort_unstable();
+        dependents.dedup();
+    }
+    dependents
+}
+
+/// Order release types from least to most significant, with prereleases
+/// below patch releases.
+pub(crate) fn release_precedence(release: ReleaseType) -> u8 {
+    match release {
+        ReleaseType::Major => 3,
+        ReleaseType::Minor => 2,
+        ReleaseType::Patch => 1,
+        ReleaseType::Prerelease => 0,
+    }
+}
+
+/// Give a patch release to every internal package depending, directly or
+/// transitively, on a released package without a release of its own,
+/// recording the dependency whose release cascaded to it.
+pub(crate) fn cascade_releases<'a>(
+    dependents: &HashMap<&'a str, Vec<&'a str>>,
+    releases: &mut BTreeMap<&'a str, (ReleaseType, Option<&'a str>)>,
+) {
+    let mut to_visit: VecDeque<&str> = releases.keys().copied().collect();
+    while let Some(package_name) = to_visit.pop_front() {
+        for dependent in dependents.get(package_name).into_iter().flatten() {
+            if releases.contains_key(*dependent) {
+                continue;
+            }
+            releases.insert(dependent, (ReleaseType::Patch, Some(package_name)));
+            to_visit.push_back(dependent);
+        }
+    }
+}
+
+/// Apply new versions to package manifests in memory and pin internal
+/// dependencies to them, returning the names of the modified packages.
+pub(crate) fn apply_versions(
+    root: &Path,
+    configuration: &MonorepoConfiguration,
+    package_manifests_by_package_name: &mut HashMap<String, PackageManifest>,
+    versions: &BTreeMap<String, Version>,
+) -> Result<HashSet<String>, VersionError> {
+    let mut modified_package_names = HashSet::new();
+    for (package_name, version) in versions {
+        if let Some(package_manifest) = package_manifests_by_package_name.get_mut(package_name) {
+            package_manifest.contents.version = version.to_string();
+            modified_package_names.insert(package_name.to_owned());
+        }
+    }
+
+    let (pinned_package_names, non_string_versions) =
+        pin_package_manifests(root, configuration, package_manifests_by_package_name, None)?;
+    // Write nothing rather than leave some dependents unpinned
+    if !non_string_versions.is_empty() {
+        Err(PinError::from(PinErrorKind::NonStringVersionNumbers(
+            non_string_versions,
+        )))?;
+    }
+    modified_package_names.extend(pinned_package_names);
+    Ok(modified_package_names)
+}
+
+fn parse_version(package_manifest: &PackageManifest) -> Result<Version, VersionError> {
+    Version::parse(&package_manifest.contents.version).map_err(|err| VersionError {
+        kind: VersionErrorKind::InvalidVersion(package_manifest.contents.name.to_owned(), err),
+    })
+}
+
+struct PlannedVersions {
+    plan: VersionPlan,
+    package_manifests_by_package_name: HashMap<String, PackageManifest>,
+    modified_package_names: HashSet<String>,
+}
+
+fn plan_internal(
+    root: &Path,
+    package_names: &[String],
+    bump: &Bump,
+    cascade: bool,
+) -> Result<PlannedVersions, VersionError> {
+    let lerna_manifest = MonorepoManifest::from_directory(root)?;
+    let configuration = MonorepoConfiguration::from_directory(root)?;
+    let mut package_manifests_by_package_name =
+        lerna_manifest.package_manifests_by_package_name()?;
+
+    let selected: Vec<&str> = match package_names.is_empty() {
+        true => package_manifests_by_package_name
+            .keys()
+            .map(String::as_str)
+            .collect(),
+        false => package_names.iter().map(String::as_str).collect(),
+    };
+
+    // The new version of each package, and the dependency it cascaded from
+    let mut next_versions: BTreeMap<String, (Version, Option<String>)> = BTreeMap::new();
+    for package_name in selected.iter() {
+        let package_manifest = package_manifests_by_package_name
+            .get(*package_name)
+            .ok_or_else(|| VersionError {
+                kind: VersionErrorKind::UnknownPackage(package_name.to_string()),
+            })?;
+        let next = bump.apply(&parse_version(package_manifest)?);
+        next_versions.insert(package_name.to_string(), (next, None));
+    }
+
+    if cascade {
+        let dependents = internal_dependents(&package_manifests_by_package_name);
+        let cascaded_bump = bump.cascaded();
+        let mut to_visit: VecDeque<&str> = selected.iter().copied().collect();
+        while let Some(package_name) = to_visit.pop_front() {
+            for dependent in dependents.get(package_name).into_iter().flatten() {
+                if next_versions.contains_key(*dependent) {
+                    continue;
+                }
+                let next = cascaded_bump.apply(&parse_version(
+                    &package_manifests_by_package_name[*dependent],
+                )?);
+                next_versions.insert(dependent.to_string(), (next, Some(package_name.to_owned())));
+                to_visit.push_back(dependent);
+            }
+        }
+    }
+
+    let changes = next_versions
+        .iter()
+        .map(|(package_name, (next, cascaded_from))| VersionChange {
+            name: package_name.to_owned(),
+            current: package_manifests_by_package_name[package_name]
+                .contents
+                .version
+                .to_owned(),
+            next: next.to_string(),
+            cascaded_from: cascaded_from.to_owned(),
+        })
+        .collect();
+    let versions = next_versions
+        .into_iter()
+        .map(|(package_name, (next, _))| (package_name, next))
+        .collect();
+    let modified_package_names = apply_versions(
+        root,
+        &configuration,
+        &mut package_manifests_by_package_name,
+        &versions,
+    )?;
+
+    let mut files: Vec<PathBuf> = modified_package_names
+        .iter()
+        .map(|package_name| package_manifests_by_package_name[package_name].path())
+        .collect();
+    files.sort_unstable();
+
+    Ok(PlannedVersions {
+        plan: VersionPlan { changes, files },
+        package_manifests_by_package_name,
+        modified_package_names,
+    })
+}
+
+/// Compute the new versions of the specified packages, or of every internal
+/// package when none is specified, without writing any files. With
+/// `cascade`, internal packages depending on a bumped package, directly or
+/// transitively, are bumped too.
+pub fn plan<P>(
+    root: P,
+    package_names: &[String],
+    bump: &Bump,
+    cascade: bool,
+) -> Result<VersionPlan, VersionError>
+where
+    P: AsRef<Path>,
+{
+    Ok(plan_internal(root.as_ref(), package_names, bump, cascade)?.plan)
+}
+
+/// Bump the versions of packages as in `plan`, and pin every internal
+/// dependency to the new versions. No package manifest is modified unless
+/// every one of them can be written; should replacing one fail, those
+/// replaced before it keep their new contents.
+pub fn modify<P>(
+    root: P,
+    package_names: &[String],
+    bump: &Bump,
+    cascade: bool,
+) -> Result<VersionPlan, VersionError>
+where
+    P: AsRef<Path>,
+{
+    fn inner(
+        root: &Path,
+        package_names: &[String],
+        bump: &Bump,
+        cascade: bool,
+    ) -> Result<VersionPlan, VersionError> {
+        let PlannedVersions {
+            plan,
+            package_manifests_by_package_name,
+            modified_package_names,
+        } = plan_internal(root, package_names, bump, cascade)?;
+        write_all(
+            root,
+            package_manifests_by_package_name
+                .into_values()
+                .filter(|package_manifest| {
+                    modified_package_names.contains(&package_manifest.contents.name)
+                }),
+        )?;
+        Ok(plan)
+    }
+    inner(root.as_ref(), package_names, bump, cascade)
+}
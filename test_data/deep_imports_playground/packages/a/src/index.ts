@@ -0,0 +1,7 @@
+import { b } from "@typescript-tools/test-b";
+import { button } from "@typescript-tools/test-b/features/button";
+import { state } from "@typescript-tools/test-b/features/internal/state";
+import { thing } from "@typescript-tools/test-b/src/internal/thing";
+import { c } from "@typescript-tools/test-c";
+import { main } from "@typescript-tools/test-c/lib/index";
+import { helper } from "@typescript-tools/test-c/lib/helper";
@@ -0,0 +1,11 @@
+import React from "react";
+import { b } from "@typescript-tools/test-b";
+import * as fs from "fs";
+export * from "./local";
+
+// import unused from "commented-out";
+const message = "import x from 'in-a-string'";
+
+export async function load() {
+  return import("lodash/fp");
+}
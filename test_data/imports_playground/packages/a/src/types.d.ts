@@ -0,0 +1 @@
+import "declared-in-declaration-file";
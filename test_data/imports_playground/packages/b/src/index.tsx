@@ -0,0 +1,2 @@
+const React = require("react");
+export const b = <div />;
@@ -0,0 +1,97 @@
+mod common;
+
+use std::path::Path;
+
+use common::tagged_playground;
+use tempdir::TempDir;
+use typescript_tools::version::Bump;
+use utilities::commit;
+
+/// Replace abbreviated commit hashes, which differ between runs.
+fn redact_hashes(changelog: &str) -> String {
+    changelog
+        .lines()
+        .map(|line| match line.rfind(" (") {
+            Some(index) if line.starts_with("- ") && line.ends_with(')') => {
+                format!("{} (<hash>)", &line[..index])
+            }
+            _ => line.to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn changelog_should_group_conventional_commits_and_updated_dependencies(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let directory = TempDir::new("typescript-tools-test-changelog")?;
+    let root = directory.path();
+    tagged_playground(root)?;
+    commit(root, "packages/a/index.ts", "fix: handle empty input")?;
+    commit(root, "packages/a/index.ts", "feat(cli): add option")?;
+    commit(root, "packages/a/index.ts", "chore: tidy")?;
+    commit(root, "packages/c/index.ts", "docs: explain usage")?;
+    std::fs::write(
+        root.join("packages/a/CHANGELOG.md"),
+        "# @typescript-tools/release-a\n\n## 1.0.0\n\n- Initial release\n",
+    )?;
+    typescript_tools::version::modify(
+        root,
+        &["@typescript-tools/release-a".to_owned()],
+        &Bump::parse("minor", None)?,
+        true,
+    )?;
+
+    // Act
+    let changelogs = typescript_tools::changelog::modify(root)?;
+
+    // Assert
+    assert_eq!(
+        vec![
+            Path::new("packages/a/CHANGELOG.md"),
+            Path::new("packages/b/CHANGELOG.md"),
+        ],
+        changelogs
+            .iter()
+            .map(|changelog| changelog.path.as_path())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        "# @typescript-tools/release-a
+
+## 1.1.0
+
+### Features
+
+- **cli:** add option (<hash>)
+
+### Bug Fixes
+
+- handle empty input (<hash>)
+
+## 1.0.0
+
+- Initial release",
+        redact_hashes(&std::fs::read_to_string(
+            root.join("packages/a/CHANGELOG.md")
+        )?)
+    );
+    assert_eq!(
+        "# Changelog
+
+## 2.1.1
+
+### Updated Dependencies
+
+- @typescript-tools/release-a@1.1.0
+",
+        std::fs::read_to_string(root.join("packages/b/CHANGELOG.md"))?
+    );
+    assert!(!root.join("packages/c/CHANGELOG.md").exists());
+
+    // Generating changelogs again does not repeat sections
+    assert!(typescript_tools::changelog::modify(root)?.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::path::Path;
+
+use tempdir::TempDir;
+use typescript_tools::changeset::{ChangesetError, ChangesetErrorKind};
+use typescript_tools::semver::ReleaseType;
+use utilities::recursive_copy;
+
+fn read_package_manifest(
+    root: &Path,
+    package: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let path = root.join("packages").join(package).join("package.json");
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+#[test]
+fn changeset_add_should_write_changeset() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-changeset")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let path = typescript_tools::changeset::add(
+        root,
+        &[
+            "@typescript-tools/version-b".to_owned(),
+            "@typescript-tools/version-a".to_owned(),
+        ],
+        ReleaseType::Minor,
+        "Add an option to the parser",
+    )?;
+    let second = typescript_tools::changeset::add(
+        root,
+        &["@typescript-tools/version-d".to_owned()],
+        ReleaseType::Patch,
+        "Add an option to the parser",
+    )?;
+
+    // Assert
+    assert_eq!(Path::new(".changeset/add-an-option-to-the-parser.md"), path);
+    assert_eq!(
+        Path::new(".changeset/add-an-option-to-the-parser-2.md"),
+        second
+    );
+    assert_eq!(
+        "---
+\"@typescript-tools/version-a\": minor
+\"@typescript-tools/version-b\": minor
+---
+
+Add an option to the parser
+",
+        std::fs::read_to_string(root.join(path))?
+    );
+    assert_eq!(2, typescript_tools::changeset::read(root)?.len());
+
+    Ok(())
+}
+
+#[test]
+fn changeset_add_should_reject_prereleases() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-changeset")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let result = typescript_tools::changeset::add(
+        root,
+        &[String::from("@typescript-tools/version-a")],
+        ReleaseType::Prerelease,
+        "Try a beta",
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(ChangesetError {
+            kind: ChangesetErrorKind::UnsupportedRelease {
+                0: ReleaseType::Prerelease,
+                ..
+            },
+            ..
+        })
+    ));
+    assert!(!root.join(".changeset").exists());
+
+    Ok(())
+}
+
+#[test]
+fn changeset_apply_should_bump_pin_and_write_changelogs() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-changeset")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    typescript_tools::changeset::add(
+        root,
+        &["@typescript-tools/version-a".to_owned()],
+        ReleaseType::Patch,
+        "Fix parsing of empty input",
+    )?;
+    typescript_tools::changeset::add(
+        root,
+        &["@typescript-tools/version-a".to_owned()],
+        ReleaseType::Minor,
+        "Add a strict mode\n\nStrict mode rejects unknown keys.",
+    )?;
+
+    // Act
+    let plan = typescript_tools::changeset::apply(root)?;
+
+    // Assert
+    let changes: Vec<String> = plan
+        .versions
+        .changes
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        vec![
+            "@typescript-tools/version-a: 1.0.0 -> 1.1.0",
+            "@typescript-tools/version-b: 1.2.0 -> 1.2.1 (depends on @typescript-tools/version-a)",
+            "@typescript-tools/version-c: 0.3.1 -> 0.3.2 (depends on @typescript-tools/version-b)",
+        ],
+        changes
+    );
+    let b = read_package_manifest(root, "b")?;
+    assert_eq!("1.2.1", b["version"].as_str().unwrap());
+    assert_eq!(
+        "1.1.0",
+        b["dependencies"]["@typescript-tools/version-a"]
+            .as_str()
+            .unwrap()
+    );
+    typescript_tools::pin::lint(root)?;
+
+    assert_eq!(
+        "# Changelog
+
+## 1.1.0
+
+### Minor Changes
+
+- Add a strict mode
+
+  Strict mode rejects unknown keys.
+
+### Patch Changes
+
+- Fix parsing of empty input
+",
+        std::fs::read_to_string(root.join("packages/a/CHANGELOG.md"))?
+    );
+    assert_eq!(
+        "# Changelog
+
+## 1.2.1
+
+### Updated Dependencies
+
+- @typescript-tools/version-a@1.1.0
+",
+        std::fs::read_to_string(root.join("packages/b/CHANGELOG.md"))?
+    );
+    assert!(!root.join("packages/d/CHANGELOG.md").exists());
+    assert!(typescript_tools::changeset::read(root)?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn changeset_apply_should_reject_unknown_package() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-changeset")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    std::fs::create_dir(root.join(".changeset"))?;
+    std::fs::write(
+        root.join(".changeset/unknown.md"),
+        "---\n\"@typescript-tools/version-z\": patch\n---\n\nOops\n",
+    )?;
+
+    // Act
+    let result = typescript_tools::changeset::apply(root);
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(ChangesetError {
+            kind: ChangesetErrorKind::UnknownPackage { 0: package_name, .. },
+            ..
+        }) if package_name == "@typescript-tools/version-z"
+    ));
+    assert_eq!(
+        "1.0.0",
+        read_package_manifest(root, "a")?["version"]
+            .as_str()
+            .unwrap()
+    );
+    assert!(root.join(".changeset/unknown.md").exists());
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::io;
+use std::path::Path;
+
+use utilities::{git, recursive_copy};
+
+/// Create a git repository at `root` from the release playground, with every
+/// package tagged at its current version.
+pub fn tagged_playground<P>(root: P) -> Result<(), io::Error>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+    recursive_copy("test_data/release_playground", root)?;
+    git(root, &["init", "-q", "-b", "main"])?;
+    git(root, &["add", "-A"])?;
+    git(root, &["commit", "-q", "-m", "chore: initial commit"])?;
+    for tag in [
+        "@typescript-tools/release-a@1.0.0",
+        "@typescript-tools/release-b@2.1.0",
+        "@typescript-tools/release-c@0.1.0",
+    ] {
+        git(root, &["tag", tag])?;
+    }
+    Ok(())
+}
@@ -1,5 +1,9 @@
+use std::path::Path;
+
 use tempdir::TempDir;
+use typescript_tools::configuration_file::ConfigurationFile;
 use typescript_tools::link::LinkLintError;
+use typescript_tools::typescript_config::TypescriptConfig;
 use utilities::recursive_copy;
 
 #[test]
@@ -35,3 +39,55 @@ fn link_should_correct_incorrect_project_references() -> Result<(), Box<dyn std:
 
     Ok(())
 }
+
+#[test]
+fn link_should_report_references_to_add() -> Result<(), LinkLintError> {
+    let root = "test_data/project_references_missing";
+    let report = typescript_tools::link::lint_report(root)?;
+    let out_of_date: Vec<_> = report.iter().collect();
+    assert_eq!(1, out_of_date.len());
+    assert_eq!(
+        Path::new("packages/b/tsconfig.json"),
+        out_of_date[0].path.as_path()
+    );
+    assert_eq!(vec!["../a"], out_of_date[0].references_to_add);
+    assert!(out_of_date[0].references_to_remove.is_empty());
+    Ok(())
+}
+
+#[test]
+fn link_should_manage_additional_project_references() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/secondary_project_references_playground";
+    let root = TempDir::new("typescript-tools-test-link")?;
+    let root = root.path().join("secondary_project_references_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    assert!(typescript_tools::link::lint(root).is_err());
+
+    // Act
+    typescript_tools::link::modify(root)?;
+
+    // Assert
+    typescript_tools::link::lint(root)?;
+    let test_config = TypescriptConfig::from_directory(root, Path::new("packages/b/test"))?;
+    assert_eq!(
+        vec!["..", "../../a", "../../c"],
+        test_config
+            .project_references()
+            .into_iter()
+            .map(|reference| reference.path)
+            .collect::<Vec<_>>()
+    );
+    let examples_config = TypescriptConfig::from_directory(root, Path::new("packages/b/examples"))?;
+    assert_eq!(
+        vec!["..", "../../a"],
+        examples_config
+            .project_references()
+            .into_iter()
+            .map(|reference| reference.path)
+            .collect::<Vec<_>>()
+    );
+
+    Ok(())
+}
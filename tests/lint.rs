@@ -1,4 +1,6 @@
-use typescript_tools::lint::LintError;
+use tempdir::TempDir;
+use typescript_tools::lint::{DependencyVersionStrategy, LintError, LintErrorKind};
+use utilities::recursive_copy;
 
 #[test]
 fn lint_happy_path_should_not_error() -> Result<(), LintError> {
@@ -15,3 +17,305 @@ fn lint_should_error_when_multiple_version_of_an_external_dependency_are_used()
             .is_err()
     );
 }
+
+#[test]
+fn lint_should_rewrite_dependency_to_highest_version() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/external_dependency_multiple_versions";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root.path().join("external_dependency_multiple_versions");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        &["@typescript-tools/external"],
+        &DependencyVersionStrategy::Highest,
+    )?;
+
+    // Assert
+    typescript_tools::lint::lint_dependency_version(root, &["@typescript-tools/external"])?;
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/a/package.json"),
+    )?)?;
+    assert_eq!(
+        "2.0.0",
+        package_manifest["dependencies"]["@typescript-tools/external"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_rewrite_dependency_to_explicit_version() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/external_dependency_multiple_versions";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root.path().join("external_dependency_multiple_versions");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        &["@typescript-tools/external"],
+        &DependencyVersionStrategy::Explicit(String::from("^3.0.0")),
+    )?;
+
+    // Assert
+    for package in ["a", "b"] {
+        let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            root.join("packages").join(package).join("package.json"),
+        )?)?;
+        assert_eq!(
+            "^3.0.0",
+            package_manifest["dependencies"]["@typescript-tools/external"]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_only_rewrite_version_ranges() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/external_dependency_specifiers_playground";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root
+        .path()
+        .join("external_dependency_specifiers_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        &["@typescript-tools/external"],
+        &DependencyVersionStrategy::Highest,
+    )?;
+
+    // Assert
+    for (package, expected) in [
+        ("a", "2.0.0"),
+        ("b", "2.0.0"),
+        ("c", "npm:@typescript-tools/other@^3.0.0"),
+        ("d", "github:typescript-tools/external#v1.0.0"),
+    ] {
+        let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            root.join("packages").join(package).join("package.json"),
+        )?)?;
+        assert_eq!(
+            expected,
+            package_manifest["dependencies"]["@typescript-tools/external"]
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_refuse_explicit_version_without_dependencies(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/external_dependency_multiple_versions";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root.path().join("external_dependency_multiple_versions");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    let no_dependencies: &[&str] = &[];
+
+    // Act
+    let result = typescript_tools::lint::modify_dependency_version(
+        root,
+        no_dependencies,
+        &DependencyVersionStrategy::Explicit(String::from("^3.0.0")),
+    );
+
+    // Assert
+    assert!(matches!(
+        result,
+        Err(LintError {
+            kind: LintErrorKind::ExplicitVersionWithoutDependencies { .. },
+            ..
+        })
+    ));
+    assert!(
+        typescript_tools::lint::lint_dependency_version(root, &["@typescript-tools/external"])
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_check_every_external_dependency_by_default() -> Result<(), LintError> {
+    let root = "test_data/external_dependencies_inconsistent";
+    let no_dependencies: &[&str] = &[];
+    let report = typescript_tools::lint::lint_dependency_version_report(root, no_dependencies)?;
+    assert_eq!(
+        vec!["inconsistent"],
+        report
+            .iter()
+            .map(|inconsistency| inconsistency.dependency.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(typescript_tools::lint::lint_dependency_version(root, no_dependencies).is_err());
+    Ok(())
+}
+
+#[test]
+fn lint_should_rewrite_every_external_dependency_by_default(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/external_dependencies_inconsistent";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root.path().join("external_dependencies_inconsistent");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    let no_dependencies: &[&str] = &[];
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        no_dependencies,
+        &DependencyVersionStrategy::Highest,
+    )?;
+
+    // Assert
+    typescript_tools::lint::lint_dependency_version(root, no_dependencies)?;
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/a/package.json"),
+    )?)?;
+    assert_eq!("2.0.0", package_manifest["dependencies"]["inconsistent"]);
+    assert_eq!("18.0.0", package_manifest["devDependencies"]["@types/node"]);
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_report_version_policy_violations() -> Result<(), LintError> {
+    let root = "test_data/dependency_version_policy_playground";
+    let no_dependencies: &[&str] = &[];
+    let report = typescript_tools::lint::lint_dependency_version_report(root, no_dependencies)?;
+    assert_eq!(0, report.iter().count());
+    assert_eq!(
+        serde_json::json!([
+            {
+                "kind": "bannedDependency",
+                "path": "packages/a/package.json",
+                "dependency": "moment",
+                "group": "dependencies",
+                "reason": "use date-fns instead"
+            },
+            {
+                "kind": "disallowedVersion",
+                "path": "packages/a/package.json",
+                "dependency": "react",
+                "group": "dependencies",
+                "actual": "^18.0.0",
+                "allowed": ["^18.2.0"]
+            }
+        ]),
+        serde_json::to_value(report.policy_violations().collect::<Vec<_>>()).unwrap()
+    );
+    Ok(())
+}
+
+#[test]
+fn lint_should_check_consistency_of_dependencies_governed_by_policy() -> Result<(), LintError> {
+    let root = "test_data/dependency_version_policy_inconsistent";
+    let report = typescript_tools::lint::lint_dependency_version_report(root, &["react"])?;
+    assert_eq!(0, report.policy_violations().count());
+    assert_eq!(
+        vec!["react"],
+        report
+            .iter()
+            .map(|inconsistency| inconsistency.dependency.as_str())
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn lint_should_rewrite_disallowed_versions_to_the_first_allowed_version(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/dependency_version_policy_playground";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root.path().join("dependency_version_policy_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        &["react"],
+        &DependencyVersionStrategy::MostCommon,
+    )?;
+
+    // Assert
+    typescript_tools::lint::lint_dependency_version(root, &["react"])?;
+    for (package, expected) in [("a", "^18.2.0"), ("b", "^17.0.0")] {
+        let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+            root.join("packages").join(package).join("package.json"),
+        )?)?;
+        assert_eq!(expected, package_manifest["dependencies"]["react"]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_keep_ranges_within_an_allowed_range() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/dependency_version_policy_range_playground";
+    let root = TempDir::new("typescript-tools-test-lint")?;
+    let root = root
+        .path()
+        .join("dependency_version_policy_range_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    let report = typescript_tools::lint::lint_dependency_version_report(root, &["react"])?;
+    assert_eq!(0, report.policy_violations().count());
+
+    // Act
+    typescript_tools::lint::modify_dependency_version(
+        root,
+        &["react"],
+        &DependencyVersionStrategy::MostCommon,
+    )?;
+
+    // Assert
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/a/package.json"),
+    )?)?;
+    assert_eq!("^18.3.0", package_manifest["dependencies"]["react"]);
+
+    Ok(())
+}
+
+#[test]
+fn lint_should_only_report_incompatible_ranges_in_semver_mode() -> Result<(), LintError> {
+    let root = "test_data/dependency_version_semver_playground";
+    let no_dependencies: &[&str] = &[];
+    let report = typescript_tools::lint::lint_dependency_version_report(root, no_dependencies)?;
+    assert_eq!(
+        vec!["incompatible"],
+        report
+            .iter()
+            .map(|inconsistency| inconsistency.dependency.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![("compatible", "^1.3.0")],
+        report
+            .compatible()
+            .map(|compatible| (
+                compatible.dependency.as_str(),
+                compatible.suggested.as_str()
+            ))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
@@ -0,0 +1,37 @@
+use typescript_tools::lint::boundaries::BoundariesLintError;
+
+#[test]
+fn lint_boundaries_happy_path_should_not_error() -> Result<(), BoundariesLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::boundaries::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_boundaries_should_report_the_offending_edge_and_rule() -> Result<(), BoundariesLintError> {
+    let root = "test_data/boundaries_playground";
+    let report = typescript_tools::lint::boundaries::lint_report(root)?;
+    assert_eq!(
+        vec![
+            (
+                "@typescript-tools/billing",
+                "@typescript-tools/server",
+                "layer:ui may not depend on layer:server"
+            ),
+            (
+                "@typescript-tools/billing",
+                "@typescript-tools/server",
+                "scope:billing may only depend on scope:shared"
+            ),
+        ],
+        report
+            .iter()
+            .map(|violation| (
+                violation.package.as_str(),
+                violation.dependency.as_str(),
+                violation.rule.as_str()
+            ))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use typescript_tools::lint::deep_imports::DeepImportsLintError;
+
+#[test]
+fn lint_deep_imports_happy_path_should_not_error() -> Result<(), DeepImportsLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::deep_imports::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_deep_imports_should_detect_unexported_modules() -> Result<(), DeepImportsLintError> {
+    let root = "test_data/deep_imports_playground";
+    let report = typescript_tools::lint::deep_imports::lint_report(root)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(1, packages.len());
+    assert_eq!("@typescript-tools/test-a", packages[0].name);
+    assert_eq!(
+        vec![
+            ("@typescript-tools/test-b/features/internal/state", 3),
+            ("@typescript-tools/test-b/src/internal/thing", 4),
+            ("@typescript-tools/test-c/lib/helper", 7),
+        ],
+        packages[0]
+            .imports
+            .iter()
+            .map(|import| (import.specifier.as_str(), import.line))
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use typescript_tools::lint::entry_points::{EntryPointIssue, EntryPointLintError};
+
+#[test]
+fn lint_entry_points_happy_path_should_not_error() -> Result<(), EntryPointLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::entry_points::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_entry_points_should_detect_unemitted_entry_points() -> Result<(), EntryPointLintError> {
+    let root = "test_data/entry_points_mismatch";
+    let report = typescript_tools::lint::entry_points::lint_report(root)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(2, packages.len());
+
+    let a = &packages[0].issues;
+    assert_eq!(2, a.len());
+    assert!(matches!(
+        &a[0],
+        EntryPointIssue::OutsideOutputDirectory { field, .. } if field == "main"
+    ));
+    assert!(matches!(
+        &a[1],
+        EntryPointIssue::DeclarationsNotEmitted { field, .. } if field == "types"
+    ));
+
+    let b = &packages[1].issues;
+    assert_eq!(1, b.len());
+    assert!(matches!(
+        &b[0],
+        EntryPointIssue::MissingSource { field, .. } if field == r#"exports["./util"]"#
+    ));
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use typescript_tools::lint::exports::ExportsLintError;
+use typescript_tools::package_exports::ExportsIssue;
+
+#[test]
+fn lint_exports_happy_path_should_not_error() -> Result<(), ExportsLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::exports::lint(root, true)?;
+    Ok(())
+}
+
+#[test]
+fn lint_exports_should_detect_invalid_shapes() -> Result<(), ExportsLintError> {
+    let root = "test_data/invalid_package_exports";
+    let report = typescript_tools::lint::exports::lint_report(root, false)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(2, packages.len());
+    assert_eq!("@typescript-tools/test-a", packages[0].name);
+    assert!(matches!(
+        packages[0].issues[..],
+        [ExportsIssue::MixedSubpathsAndConditions { .. }]
+    ));
+    assert_eq!("@typescript-tools/test-b", packages[1].name);
+    assert!(matches!(
+        packages[1].issues[..],
+        [ExportsIssue::InvalidTarget { .. }]
+    ));
+    Ok(())
+}
+
+#[test]
+fn lint_exports_should_detect_missing_targets() -> Result<(), ExportsLintError> {
+    let root = "test_data/invalid_package_exports";
+    let report = typescript_tools::lint::exports::lint_report(root, true)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(3, packages.len());
+    assert_eq!("@typescript-tools/test-c", packages[2].name);
+    assert!(matches!(
+        &packages[2].issues[..],
+        [ExportsIssue::MissingTarget { target, .. }] if target == "./lib/features/*.js"
+    ));
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+
+use typescript_tools::lint::imports::{ImportIssue, ImportsLintError};
+
+#[test]
+fn lint_imports_happy_path_should_not_error() -> Result<(), ImportsLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::imports::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_imports_should_detect_undeclared_and_unused_dependencies() -> Result<(), ImportsLintError> {
+    let root = "test_data/imports_playground";
+    let report = typescript_tools::lint::imports::lint_report(root)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(1, packages.len());
+    assert_eq!("@typescript-tools/test-a", packages[0].name);
+    assert!(matches!(
+        &packages[0].issues[..],
+        [
+            ImportIssue::Undeclared { dependency: internal_dependency, internal: true, file: internal_file, line: 2, .. },
+            ImportIssue::Undeclared { dependency: external_dependency, internal: false, line: 10, .. },
+            ImportIssue::Unused { dependency: unused_dependency, line: Some(7), .. },
+        ] if internal_dependency == "@typescript-tools/test-b"
+            && internal_file == &PathBuf::from("packages/a/src/index.ts")
+            && external_dependency == "lodash"
+            && unused_dependency == "left-pad"
+    ));
+    Ok(())
+}
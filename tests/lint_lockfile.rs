@@ -0,0 +1,87 @@
+use typescript_tools::lint::lockfile::LockfileLintError;
+use typescript_tools::lockfile::{Lockfile, LockfileFormat, Resolution};
+
+fn issues(root: &str) -> Result<Vec<(String, String)>, LockfileLintError> {
+    let report = typescript_tools::lint::lockfile::lint_report(root)?;
+    Ok(report
+        .iter()
+        .flat_map(|package| {
+            package
+                .issues
+                .iter()
+                .map(|issue| (package.name.to_owned(), issue.to_string()))
+        })
+        .collect())
+}
+
+#[test]
+fn lint_lockfile_should_report_npm_issues() -> Result<(), LockfileLintError> {
+    assert_eq!(
+        vec![
+            (
+                String::from("@typescript-tools/a"),
+                String::from("@typescript-tools/b@^1.0.0 is an internal package but is locked to version 1.0.0 from the registry")
+            ),
+            (
+                String::from("@typescript-tools/a"),
+                String::from("typescript@^5.0.0 is missing from the lockfile")
+            ),
+            (
+                String::from("@typescript-tools/b"),
+                String::from("left-pad@^1.3.0 is locked to version 1.1.0 which does not satisfy it")
+            ),
+        ],
+        issues("test_data/lockfile_npm_playground")?
+    );
+    Ok(())
+}
+
+#[test]
+fn lint_lockfile_should_accept_yarn_classic_lockfile() -> Result<(), LockfileLintError> {
+    typescript_tools::lint::lockfile::lint("test_data/lockfile_yarn_classic_playground")?;
+    Ok(())
+}
+
+#[test]
+fn lint_lockfile_should_report_yarn_berry_issues() -> Result<(), LockfileLintError> {
+    assert_eq!(
+        vec![(
+            String::from("@typescript-tools/b"),
+            String::from("left-pad@^1.3.0 is locked to version 1.1.0 which does not satisfy it")
+        )],
+        issues("test_data/lockfile_yarn_berry_playground")?
+    );
+    Ok(())
+}
+
+#[test]
+fn lint_lockfile_should_accept_pnpm_lockfile() -> Result<(), LockfileLintError> {
+    typescript_tools::lint::lockfile::lint("test_data/lockfile_pnpm_playground")?;
+    Ok(())
+}
+
+#[test]
+fn lockfile_should_resolve_dependencies_per_package() -> Result<(), Box<dyn std::error::Error>> {
+    let lockfile = Lockfile::from_directory("test_data/lockfile_npm_playground")?;
+    assert_eq!(LockfileFormat::Npm, lockfile.format());
+    assert_eq!(
+        Some(Resolution::Version(String::from("1.1.0"))),
+        lockfile.resolve("packages/b".as_ref(), "left-pad", "^1.3.0")
+    );
+    assert_eq!(
+        Some(Resolution::Link),
+        lockfile.resolve("".as_ref(), "@typescript-tools/a", "*")
+    );
+    assert_eq!(
+        vec!["1.1.0", "1.3.0"],
+        lockfile.versions("left-pad").iter().collect::<Vec<_>>()
+    );
+
+    let lockfile = Lockfile::from_directory("test_data/lockfile_pnpm_playground")?;
+    assert_eq!(LockfileFormat::Pnpm, lockfile.format());
+    assert_eq!(
+        Some(Resolution::Link),
+        lockfile.resolve("packages/a".as_ref(), "@typescript-tools/b", "^1.0.0")
+    );
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use typescript_tools::lint::peer_dependencies::{PeerDependenciesLintError, PeerDependencyIssue};
+
+#[test]
+fn lint_peer_dependencies_happy_path_should_not_error() -> Result<(), PeerDependenciesLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::peer_dependencies::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_peer_dependencies_should_detect_unmet_and_missing_peers(
+) -> Result<(), PeerDependenciesLintError> {
+    let root = "test_data/peer_dependencies_playground";
+    let report = typescript_tools::lint::peer_dependencies::lint_report(root)?;
+    let packages: Vec<_> = report.iter().collect();
+    assert_eq!(
+        vec![
+            "@typescript-tools/app-a",
+            "@typescript-tools/app-b",
+            "@typescript-tools/app-d"
+        ],
+        packages
+            .iter()
+            .map(|package| package.name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert!(matches!(
+        &packages[0].issues[..],
+        [
+            PeerDependencyIssue::Unmet { peer: theme, actual: theme_version, .. },
+            PeerDependencyIssue::Unmet { peer: react, actual: react_version, .. },
+        ] if theme == "@typescript-tools/theme"
+            && theme_version == "1.0.0"
+            && react == "react"
+            && react_version == "17.0.2"
+    ));
+    assert!(matches!(
+        &packages[1].issues[..],
+        [PeerDependencyIssue::Missing { peer, required_by, .. }]
+            if peer == "react" && required_by == "@typescript-tools/ui"
+    ));
+    assert!(matches!(
+        &packages[2].issues[..],
+        [PeerDependencyIssue::Missing { peer, required_by, .. }]
+            if peer == "react" && required_by == "@typescript-tools/widgets"
+    ));
+    Ok(())
+}
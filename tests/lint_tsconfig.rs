@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use typescript_tools::lint::tsconfig::{TsconfigIssue, TsconfigLintError};
+
+#[test]
+fn lint_tsconfig_happy_path_should_not_error() -> Result<(), TsconfigLintError> {
+    let root = "test_data/happy_path";
+    typescript_tools::lint::tsconfig::lint(root)?;
+    Ok(())
+}
+
+#[test]
+fn lint_tsconfig_should_detect_inconsistent_settings() -> Result<(), TsconfigLintError> {
+    let root = "test_data/inconsistent_typescript_settings";
+    let report = typescript_tools::lint::tsconfig::lint_report(root)?;
+    let issues: Vec<_> = report.iter().collect();
+    assert_eq!(3, issues.len());
+
+    assert!(matches!(
+        issues[0],
+        TsconfigIssue::OverlappingOutDir { project, out_dir, other_project, .. }
+            if project == &PathBuf::from("packages/a/tsconfig.json")
+            && out_dir == &PathBuf::from("dist")
+            && other_project == &PathBuf::from("packages/b/tsconfig.json")
+    ));
+    assert!(matches!(
+        issues[1],
+        TsconfigIssue::OutDirIncluded { project, including_project, .. }
+            if project == &PathBuf::from("packages/c/tsconfig.json")
+            && including_project == &PathBuf::from("packages/d/tsconfig.json")
+    ));
+    assert!(matches!(
+        issues[2],
+        TsconfigIssue::IncludeOutsideRootDir { project, include, .. }
+            if project == &PathBuf::from("packages/b/tsconfig.json")
+            && include == &PathBuf::from("packages/b/test")
+    ));
+    Ok(())
+}
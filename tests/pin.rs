@@ -1,5 +1,6 @@
+use assert_json_diff::assert_json_eq;
 use tempdir::TempDir;
-use typescript_tools::pin::PinError;
+use typescript_tools::pin::{PinError, PinErrorKind, PinLintError, SpecifierProtocol};
 use utilities::recursive_copy;
 
 #[test]
@@ -34,3 +35,163 @@ fn pin_should_correct_unpinned_internal_dependency() -> Result<(), Box<dyn std::
 
     Ok(())
 }
+
+#[test]
+fn pin_should_preserve_range_operators() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/range_pinned_dependency_playground";
+    let root = TempDir::new("typescript-tools-test-pin")?;
+    let root = root.path().join("range_pinned_dependency_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    assert!(typescript_tools::pin::lint(root).is_err());
+
+    // Act
+    typescript_tools::pin::modify(root)?;
+
+    // Assert
+    typescript_tools::pin::lint(root)?;
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/b/package.json"),
+    )?)?;
+    assert_eq!(
+        serde_json::json!({
+            "dependencies": "^1.3.0",
+            "devDependencies": "~1.3.0",
+            "peerDependencies": "^1.0.0",
+        }),
+        serde_json::json!({
+            "dependencies": package_manifest["dependencies"]["@typescript-tools/test-a"],
+            "devDependencies": package_manifest["devDependencies"]["@typescript-tools/test-a"],
+            "peerDependencies": package_manifest["peerDependencies"]["@typescript-tools/test-a"],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pin_should_convert_workspace_protocol_to_versions() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/workspace_protocol_playground";
+    let root = TempDir::new("typescript-tools-test-pin")?;
+    let root = root.path().join("workspace_protocol_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    assert!(typescript_tools::pin::lint(root).is_err());
+
+    // Act
+    typescript_tools::pin::convert(root, SpecifierProtocol::Version)?;
+
+    // Assert
+    typescript_tools::pin::lint(root)?;
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/b/package.json"),
+    )?)?;
+    assert_eq!(
+        "^1.3.0",
+        package_manifest["dependencies"]["@typescript-tools/test-a"]
+    );
+    assert_eq!(
+        "1.3.0",
+        package_manifest["devDependencies"]["@typescript-tools/test-a"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pin_should_check_aliases_and_ignore_local_paths() -> Result<(), PinLintError> {
+    let root = "test_data/dependency_specifiers";
+    typescript_tools::pin::lint(root)
+}
+
+#[test]
+fn pin_should_correct_aliases_and_ignore_local_paths() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/dependency_specifiers_playground";
+    let root = TempDir::new("typescript-tools-test-pin")?;
+    let root = root.path().join("dependency_specifiers_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+    assert!(typescript_tools::pin::lint(root).is_err());
+
+    // Act
+    typescript_tools::pin::modify(root)?;
+
+    // Assert
+    typescript_tools::pin::lint(root)?;
+    let package_manifest: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(
+        root.join("packages/b/package.json"),
+    )?)?;
+    assert_eq!(
+        serde_json::json!({
+            "dependencies": "npm:@typescript-tools/test-a@1.0.0",
+            "devDependencies": "file:../a",
+            "peerDependencies": "link:../a",
+        }),
+        serde_json::json!({
+            "dependencies": package_manifest["dependencies"]["a-alias"],
+            "devDependencies": package_manifest["devDependencies"]["@typescript-tools/test-a"],
+            "peerDependencies": package_manifest["peerDependencies"]["@typescript-tools/test-a"],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pin_should_report_unpinned_dependency_locations() -> Result<(), PinLintError> {
+    let root = "test_data/unpinned_internal_dependency";
+    let report = typescript_tools::pin::lint_report(root)?;
+    assert_json_eq!(
+        serde_json::json!([{
+            "path": "packages/b/package.json",
+            "dependencies": [{
+                "name": "@typescript-tools/test-a",
+                "group": "dependencies",
+                "actual": "0.0.0",
+                "expected": "1.0.0",
+                "line": 6,
+            }],
+            "nonStringVersions": [],
+        }]),
+        serde_json::to_value(&report).unwrap()
+    );
+    Ok(())
+}
+
+#[test]
+fn pin_should_collect_non_string_versions() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/non_string_dependency_version_playground";
+    let root = TempDir::new("typescript-tools-test-pin")?;
+    let root = root.path().join("non_string_dependency_version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let err = typescript_tools::pin::modify(root).unwrap_err();
+
+    // Assert
+    assert!(matches!(
+        err.kind,
+        PinErrorKind::NonStringVersionNumbers { 0: non_string_versions, .. } if non_string_versions.dependency_count() == 1
+    ));
+    let report = typescript_tools::pin::lint_report(root)?;
+    assert_json_eq!(
+        serde_json::json!([{
+            "path": "packages/b/package.json",
+            "dependencies": [],
+            "nonStringVersions": [{
+                "name": "external",
+                "group": "devDependencies",
+                "actual": { "version": "1.0.0" },
+                "line": 9,
+            }],
+        }]),
+        serde_json::to_value(&report)?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use typescript_tools::publish::PublishError;
+
+fn assert_plan(registry: &str) -> Result<(), PublishError> {
+    let root = "test_data/publish_playground";
+    let plan = typescript_tools::publish::plan(root, registry)?;
+
+    assert_eq!(
+        vec![
+            "@typescript-tools/publish-a@1.1.0 (packages/a)",
+            "@typescript-tools/publish-b@2.0.0 (packages/b)",
+            "@typescript-tools/publish-e@3.0.0 (packages/e)",
+        ],
+        plan.packages
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["@typescript-tools/publish-b depends on private package @typescript-tools/publish-c"],
+        plan.private_dependencies
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn publish_plan_should_read_directory_of_packuments() -> Result<(), PublishError> {
+    assert_plan("test_data/publish_playground/registry")
+}
+
+#[test]
+fn publish_plan_should_read_npm_view_snapshot() -> Result<(), PublishError> {
+    assert_plan("test_data/publish_playground/registry.json")
+}
+
+#[test]
+fn publish_plan_should_order_dependencies_first() -> Result<(), Box<dyn std::error::Error>> {
+    let root = "test_data/publish_playground";
+    let empty_registry = tempdir::TempDir::new("typescript-tools-test-publish")?;
+    let plan = typescript_tools::publish::plan(root, empty_registry.path())?;
+
+    let order: Vec<&str> = plan
+        .packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect();
+    assert_eq!(
+        vec![
+            "@typescript-tools/publish-a",
+            "@typescript-tools/publish-b",
+            "@typescript-tools/publish-d",
+            "@typescript-tools/publish-e",
+        ],
+        order
+    );
+    Ok(())
+}
@@ -2,7 +2,9 @@ use std::fs;
 
 use assert_json_diff::assert_json_eq;
 
-use typescript_tools::query::{query_internal_dependencies, InternalDependenciesFormat};
+use typescript_tools::query::{
+    query_internal_dependencies, query_resolved, InternalDependenciesFormat,
+};
 
 #[test]
 fn query_snapshot_happy_path_with_format_name() -> Result<(), Box<dyn std::error::Error>> {
@@ -23,3 +25,71 @@ fn query_snapshot_happy_path_with_format_path() -> Result<(), Box<dyn std::error
     assert_json_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn query_should_resolve_aliased_internal_dependencies() -> Result<(), Box<dyn std::error::Error>> {
+    let root = "test_data/dependency_specifiers";
+    let expected = serde_json::json!({
+        "@typescript-tools/test-a": [],
+        "@typescript-tools/test-b": ["@typescript-tools/test-a"],
+    });
+    let actual = query_internal_dependencies(root, InternalDependenciesFormat::Name)?;
+    assert_json_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn query_resolved_should_report_transitive_duplicates() -> Result<(), Box<dyn std::error::Error>> {
+    for root in [
+        "test_data/lockfile_npm_playground",
+        "test_data/lockfile_pnpm_playground",
+    ] {
+        let expected = serde_json::json!({
+            "@typescript-tools/a": {
+                "direct": "4.17.21",
+                "versions": ["3.10.1", "4.17.21"],
+            },
+        });
+        let actual = query_resolved(root, "lodash")?;
+        assert_json_eq!(expected, actual);
+    }
+    Ok(())
+}
+
+#[test]
+fn query_resolved_should_follow_links_to_internal_packages(
+) -> Result<(), Box<dyn std::error::Error>> {
+    for root in [
+        "test_data/lockfile_npm_link_playground",
+        "test_data/lockfile_pnpm_link_playground",
+        "test_data/lockfile_yarn_berry_link_playground",
+    ] {
+        let expected = serde_json::json!({
+            "@typescript-tools/a": {
+                "direct": "4.17.21",
+                "versions": ["3.10.1", "4.17.21"],
+            },
+            "@typescript-tools/b": {
+                "direct": "3.10.1",
+                "versions": ["3.10.1"],
+            },
+        });
+        let actual = query_resolved(root, "lodash")?;
+        assert_json_eq!(expected, actual);
+    }
+    Ok(())
+}
+
+#[test]
+fn query_resolved_should_report_versions_per_package() -> Result<(), Box<dyn std::error::Error>> {
+    let root = "test_data/lockfile_npm_playground";
+    let expected = serde_json::json!({
+        "@typescript-tools/b": {
+            "direct": "1.1.0",
+            "versions": ["1.1.0"],
+        },
+    });
+    let actual = query_resolved(root, "left-pad")?;
+    assert_json_eq!(expected, actual);
+    Ok(())
+}
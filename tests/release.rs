@@ -0,0 +1,117 @@
+mod common;
+
+use common::tagged_playground;
+use tempdir::TempDir;
+use typescript_tools::semver::ReleaseType;
+use utilities::{commit, git};
+
+#[test]
+fn release_plan_should_be_empty_without_commits_since_tags(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let directory = TempDir::new("typescript-tools-test-release")?;
+    let root = directory.path();
+    tagged_playground(root)?;
+    commit(root, "README.md", "feat!: document the monorepo")?;
+
+    let plan = typescript_tools::release::plan(root)?;
+
+    assert!(plan.is_empty());
+    Ok(())
+}
+
+#[test]
+fn release_plan_should_infer_releases_from_conventional_commits(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let directory = TempDir::new("typescript-tools-test-release")?;
+    let root = directory.path();
+    tagged_playground(root)?;
+    commit(root, "packages/a/index.ts", "fix: handle empty input")?;
+    commit(root, "packages/a/index.ts", "feat(a): add option")?;
+    commit(root, "packages/c/index.ts", "docs: explain usage")?;
+
+    // Act
+    let plan = typescript_tools::release::plan(root)?;
+
+    // Assert
+    let releases: Vec<(&str, &str, ReleaseType, Option<&str>)> = plan
+        .iter()
+        .map(|release| {
+            (
+                release.name.as_str(),
+                release.next.as_str(),
+                release.release,
+                release.cascaded_from.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                "@typescript-tools/release-a",
+                "1.1.0",
+                ReleaseType::Minor,
+                None
+            ),
+            (
+                "@typescript-tools/release-b",
+                "2.1.1",
+                ReleaseType::Patch,
+                Some("@typescript-tools/release-a")
+            ),
+        ],
+        releases
+    );
+    let a = plan.iter().next().unwrap();
+    assert_eq!(
+        Some("@typescript-tools/release-a@1.0.0"),
+        a.last_tag.as_deref()
+    );
+    assert_eq!(
+        vec!["feat(a): add option", "fix: handle empty input"],
+        a.commits
+            .iter()
+            .map(|commit| commit.subject.as_str())
+            .collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn release_plan_should_only_read_history_since_last_tag() -> Result<(), Box<dyn std::error::Error>>
+{
+    // Arrange
+    let directory = TempDir::new("typescript-tools-test-release")?;
+    let root = directory.path();
+    tagged_playground(root)?;
+    commit(root, "packages/c/index.ts", "feat!: rename export")?;
+    git(root, &["tag", "@typescript-tools/release-c@1.0.0"])?;
+    commit(root, "packages/c/index.ts", "fix: off by one")?;
+    commit(root, "packages/b/index.ts", "feat: add command")?;
+
+    // Act
+    let plan = typescript_tools::release::plan(root)?;
+
+    // Assert
+    let json = serde_json::to_value(&plan)?;
+    let versions: Vec<(&str, &str, &str)> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|release| {
+            (
+                release["name"].as_str().unwrap(),
+                release["next"].as_str().unwrap(),
+                release["release"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("@typescript-tools/release-b", "2.2.0", "minor"),
+            ("@typescript-tools/release-c", "0.1.1", "patch"),
+        ],
+        versions
+    );
+    Ok(())
+}
@@ -0,0 +1,184 @@
+use std::path::Path;
+
+use tempdir::TempDir;
+use typescript_tools::semver::ReleaseType;
+use typescript_tools::version::{Bump, VersionError, VersionErrorKind};
+use utilities::recursive_copy;
+
+fn read_package_manifest(
+    root: &Path,
+    package: &str,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let path = root.join("packages").join(package).join("package.json");
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn patch() -> Bump {
+    Bump::Release {
+        release: ReleaseType::Patch,
+        preid: None,
+    }
+}
+
+#[test]
+fn version_should_bump_selected_package_and_pin_dependents(
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-version")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let plan = typescript_tools::version::modify(
+        root,
+        &["@typescript-tools/version-a".to_owned()],
+        &Bump::parse("minor", None)?,
+        false,
+    )?;
+
+    // Assert
+    assert_eq!(1, plan.changes.len());
+    assert_eq!("1.1.0", plan.changes[0].next);
+    assert_eq!(
+        vec![
+            Path::new("packages/a/package.json"),
+            Path::new("packages/b/package.json"),
+        ],
+        plan.files
+    );
+    assert_eq!(
+        "1.1.0",
+        read_package_manifest(root, "a")?["version"]
+            .as_str()
+            .unwrap()
+    );
+    let b = read_package_manifest(root, "b")?;
+    assert_eq!("1.2.0", b["version"].as_str().unwrap());
+    assert_eq!(
+        "1.1.0",
+        b["dependencies"]["@typescript-tools/version-a"]
+            .as_str()
+            .unwrap()
+    );
+    typescript_tools::pin::lint(root)?;
+
+    Ok(())
+}
+
+#[test]
+fn version_should_cascade_to_transitive_dependents() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-version")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let plan = typescript_tools::version::modify(
+        root,
+        &["@typescript-tools/version-a".to_owned()],
+        &Bump::parse("major", None)?,
+        true,
+    )?;
+
+    // Assert
+    let changes: Vec<String> = plan.changes.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        vec![
+            "@typescript-tools/version-a: 1.0.0 -> 2.0.0",
+            "@typescript-tools/version-b: 1.2.0 -> 1.2.1 (depends on @typescript-tools/version-a)",
+            "@typescript-tools/version-c: 0.3.1 -> 0.3.2 (depends on @typescript-tools/version-b)",
+        ],
+        changes
+    );
+    let c = read_package_manifest(root, "c")?;
+    assert_eq!("0.3.2", c["version"].as_str().unwrap());
+    assert_eq!(
+        "1.2.1",
+        c["devDependencies"]["@typescript-tools/version-b"]
+            .as_str()
+            .unwrap()
+    );
+    assert_eq!(
+        "2.0.0",
+        read_package_manifest(root, "d")?["version"]
+            .as_str()
+            .unwrap()
+    );
+    typescript_tools::pin::lint(root)?;
+
+    Ok(())
+}
+
+#[test]
+fn version_should_cascade_prereleases() -> Result<(), VersionError> {
+    let root = "test_data/version_playground";
+    let plan = typescript_tools::version::plan(
+        root,
+        &["@typescript-tools/version-b".to_owned()],
+        &Bump::Release {
+            release: ReleaseType::Prerelease,
+            preid: Some("beta".to_owned()),
+        },
+        true,
+    )?;
+    let versions: Vec<(&str, &str)> = plan
+        .changes
+        .iter()
+        .map(|change| (change.name.as_str(), change.next.as_str()))
+        .collect();
+    assert_eq!(
+        vec![
+            ("@typescript-tools/version-b", "1.2.1-beta.0"),
+            ("@typescript-tools/version-c", "0.3.2-beta.0"),
+        ],
+        versions
+    );
+    Ok(())
+}
+
+#[test]
+fn version_dry_run_should_not_write() -> Result<(), Box<dyn std::error::Error>> {
+    // Arrange
+    let template = "test_data/version_playground";
+    let root = TempDir::new("typescript-tools-test-version")?;
+    let root = root.path().join("version_playground");
+    let root = root.as_path();
+    recursive_copy(template, root)?;
+
+    // Act
+    let plan = typescript_tools::version::plan(root, &[], &Bump::parse("3.0.0", None)?, false)?;
+
+    // Assert
+    assert_eq!(4, plan.changes.len());
+    assert_eq!(4, plan.files.len());
+    assert_eq!(
+        "1.0.0",
+        read_package_manifest(root, "a")?["version"]
+            .as_str()
+            .unwrap()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn version_should_reject_unknown_package() {
+    let root = "test_data/version_playground";
+    let result = typescript_tools::version::modify(
+        root,
+        &["@typescript-tools/version-z".to_owned()],
+        &patch(),
+        false,
+    );
+    assert!(matches!(
+        result,
+        Err(VersionError {
+            kind: VersionErrorKind::UnknownPackage { 0: package_name, .. },
+            ..
+        }) if package_name == "@typescript-tools/version-z"
+    ));
+}
@@ -1,6 +1,7 @@
 use std::{
-    fs,
+    fs, io,
     path::{Path, PathBuf},
+    process::Command,
 };
 
 pub fn recursive_copy<U, V>(from: U, to: V) -> Result<(), std::io::Error>
@@ -49,3 +50,42 @@ where
 
     Ok(())
 }
+
+/// Run git in `directory` with a fixed identity and without commit signing,
+/// failing when git exits unsuccessfully.
+pub fn git<P>(directory: P, args: &[&str]) -> Result<(), io::Error>
+where
+    P: AsRef<Path>,
+{
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(directory.as_ref())
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(["-c", "commit.gpgSign=false", "-c", "tag.gpgSign=false"])
+        .args(args)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .status()?;
+    match status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            status
+        ))),
+    }
+}
+
+/// Append `message` as a comment to `file` and commit every change in the
+/// repository at `root` with `message`.
+pub fn commit<P>(root: P, file: &str, message: &str) -> Result<(), io::Error>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let path = root.join(file);
+    let contents = fs::read_to_string(&path).unwrap_or_default();
+    fs::write(&path, format!("{}// {}\n", contents, message))?;
+    git(root, &["add", "-A"])?;
+    git(root, &["commit", "-q", "-m", message])
+}